@@ -1,29 +1,35 @@
-// TODO: Is this much wrapping actually worthwhile? What is the expectation? Check other crates.
+//! The ADC Control registers (0x82 and 0x83) switch the individual ADC
+//! channels on and off. A channel that's off won't update its value
+//! register, so readings from it will be stale.
 
 bitflags! {
+    /// Holds both registers with 0x82 in the high byte. Changes will need
+    /// to be committed manually.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct AdcControl: u16 {
-        /// Register address to read battery voltage from
+        /// Enables sampling the battery voltage
         const BATTERY_VOLTAGE = 1 << 15;
-        /// Register address to read battery current from
+        /// Enables sampling the battery current
         const BATTERY_CURRENT = 1 << 14;
-        /// Register address to read inbound power voltage from
+        /// Enables sampling the inbound power voltage
         const ACIN_VOLTAGE = 1 << 13;
-        /// Register address to read inbound power current from
+        /// Enables sampling the inbound power current
         const ACIN_CURRENT = 1 << 12;
-        /// Register address to read USB power voltage from
+        /// Enables sampling the USB power voltage
         const VBUS_VOLTAGE = 1 << 11;
-        /// Register address to read USB power amperage from
+        /// Enables sampling the USB power amperage
         const VBUS_CURRENT = 1 << 10;
-        /// Register address to test internal voltage to make sure it's still accurate
+        /// Enables sampling the internal (APS) voltage
         const APS_VOLTAGE = 1 << 9;
-        /// Register address for temperature sensor for battery though
+        /// Enables the temperature sensor for the battery though
         /// it can be used for any voltage between 0 and 3.3v
         const TS_FUNCTION = 1 << 8;
-        /// Register address for the internal temperature
+        /// Enables sampling the internal temperature
         const TEMPERATURE = 1 << 7;
-        /// Register address to read voltage from GPIO pin 0 (if configured)
+        /// Enables reading voltage from GPIO pin 0 (if configured)
         const GPIO0 = 1 << 3;
-        /// Register address to read voltage from GPIO pin 1 (if configured)
+        /// Enables reading voltage from GPIO pin 1 (if configured)
         const GPIO1 = 1 << 2;
     }
 }
@@ -75,6 +81,30 @@ impl AdcControl {
         self.set(Self::VBUS_VOLTAGE, value);
     }
 
+    pub fn vbus_current(&self) -> bool {
+        self.contains(Self::VBUS_CURRENT)
+    }
+
+    pub fn set_vbus_current(&mut self, value: bool) {
+        self.set(Self::VBUS_CURRENT, value);
+    }
+
+    pub fn aps_voltage(&self) -> bool {
+        self.contains(Self::APS_VOLTAGE)
+    }
+
+    pub fn set_aps_voltage(&mut self, value: bool) {
+        self.set(Self::APS_VOLTAGE, value);
+    }
+
+    pub fn ts_function(&self) -> bool {
+        self.contains(Self::TS_FUNCTION)
+    }
+
+    pub fn set_ts_function(&mut self, value: bool) {
+        self.set(Self::TS_FUNCTION, value);
+    }
+
     pub fn temperature(&self) -> bool {
         self.contains(Self::TEMPERATURE)
     }
@@ -98,4 +128,36 @@ impl AdcControl {
     pub fn set_gpio1(&mut self, value: bool) {
         self.set(Self::GPIO1, value);
     }
-}
\ No newline at end of file
+}
+
+/// How often the ADC samples its channels, set in bits 7-6 of the ADC
+/// speed register (0x84). The rest of the register configures the TS pin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AdcSampleRate {
+    Hz25 = 0b00,
+    Hz50 = 0b01,
+    Hz100 = 0b10,
+    Hz200 = 0b11,
+}
+
+impl AdcSampleRate {
+    /// Decodes bits 7-6 of register 0x84
+    pub fn from_register(value: u8) -> Self {
+        match value >> 6 {
+            0b00 => AdcSampleRate::Hz25,
+            0b01 => AdcSampleRate::Hz50,
+            0b10 => AdcSampleRate::Hz100,
+            _ => AdcSampleRate::Hz200,
+        }
+    }
+
+    /// The rate as bits 7-6 of register 0x84, the TS pin bits left clear
+    pub fn to_register(&self) -> u8 {
+        (*self as u8) << 6
+    }
+
+    /// The rate as a plain number
+    pub fn hz(&self) -> u16 {
+        25 << (*self as u8)
+    }
+}
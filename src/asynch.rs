@@ -0,0 +1,360 @@
+//! An async flavour of the driver for executors like Embassy or RTIC,
+//! built on `embedded_hal_async::i2c::I2c`. Enable it with the `async`
+//! feature.
+//!
+//! It covers the everyday part of the blocking `Axp209`: power and charging
+//! status, the output rails, charging settings, the ADC readings and IRQs.
+//! The register math is shared with the blocking driver, so both always
+//! agree, and anything missing can still be done with `read_register()`
+//! and `modify_register()`.
+//!
+//! ```ignore
+//!     let mut pmic = asynch::Axp209::new(i2c);
+//!
+//!     let level = pmic.battery_level().await?;
+//! ```
+
+use embedded_hal_async::i2c::I2c;
+
+use crate::conversions;
+use crate::{AdcControl, ChargeTargetVoltage, ChargingStatus, Error, IrqEnable, IrqStatus, Measurements,
+     PowerControl, PowerStatus, Rail, Registers, ADDRESS, BATTERY_LEVEL_MISSING};
+use crate::{Celsius, Milliamps, Millivolts};
+
+pub struct Axp209<I2C> {
+    device: I2C,
+}
+
+impl<I2C> Axp209<I2C>
+where
+    I2C: I2c,
+{
+    pub fn new(dev: I2C) -> Self {
+        Axp209 {
+            device: dev,
+        }
+    }
+
+    /// Hands the I2C bus back once you're done with the chip
+    pub fn free(self) -> I2C {
+        self.device
+    }
+
+    async fn get_8bit_register(&mut self, register: u8) -> Result<u8, Error<I2C::Error>> {
+        let mut buf: [u8; 1] = [0];
+
+        self.get_8bit_registers(register, &mut buf).await?;
+
+        Ok(buf[0])
+    }
+
+    /// Reads consecutive registers in a single transaction
+    async fn get_8bit_registers(&mut self, register: u8, buf: &mut [u8]) -> Result<(), Error<I2C::Error>> {
+        self.device.write_read(ADDRESS, &[register], buf).await?;
+
+        Ok(())
+    }
+
+    async fn set_8bit_register(&mut self, register: u8, value: u8) -> Result<(), Error<I2C::Error>> {
+        self.device.write(ADDRESS, &[register, value]).await?;
+
+        Ok(())
+    }
+
+    /// The chip only auto-increments the register address when reading, so
+    /// consecutive registers have to be written one at a time.
+    async fn set_8bit_registers(&mut self, register: u8, values: &[u8]) -> Result<(), Error<I2C::Error>> {
+        for (i, value) in values.iter().enumerate() {
+            self.set_8bit_register(register + i as u8, *value).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_adc_12bits(&mut self, register: Registers) -> Result<u16, Error<I2C::Error>> {
+        let mut recv: [u8; 2] = [0, 0];
+
+        self.get_8bit_registers(register as u8, &mut recv).await?;
+
+        Ok(conversions::adc_12bits(&recv))
+    }
+
+    /// Escape hatch for registers the driver doesn't know about (yet)
+    pub async fn read_register(&mut self, register: u8) -> Result<u8, Error<I2C::Error>> {
+        self.get_8bit_register(register).await
+    }
+
+    pub async fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<I2C::Error>> {
+        self.set_8bit_register(register, value).await
+    }
+
+    /// Reads a register, hands the value to `f` and writes back whatever it
+    /// returns
+    pub async fn modify_register(&mut self, register: u8, f: impl FnOnce(u8) -> u8) -> Result<(), Error<I2C::Error>> {
+        let value = self.get_8bit_register(register).await?;
+
+        self.set_8bit_register(register, f(value)).await
+    }
+
+    pub async fn power_status(&mut self) -> Result<PowerStatus, Error<I2C::Error>> {
+        Ok(PowerStatus::new(self.get_8bit_register(Registers::PowerStatus as u8).await?))
+    }
+
+    pub async fn charging_status(&mut self) -> Result<ChargingStatus, Error<I2C::Error>> {
+        Ok(ChargingStatus::new(self.get_8bit_register(Registers::ChargingStatus as u8).await?))
+    }
+
+    pub async fn power_control(&mut self) -> Result<PowerControl, Error<I2C::Error>> {
+        Ok(PowerControl::new(self.get_8bit_register(Registers::PowerControl as u8).await?))
+    }
+
+    pub async fn set_power_control(&mut self, value: PowerControl) -> Result<(), Error<I2C::Error>> {
+        self.set_8bit_register(Registers::PowerControl as u8, value.bits()).await
+    }
+
+    /// Turn a single output on or off, leaving the other rails alone
+    pub async fn set_rail_enabled(&mut self, rail: Rail, on: bool) -> Result<(), Error<I2C::Error>> {
+        let mut value = self.power_control().await?;
+        value.set(rail.flag(), on);
+
+        self.set_power_control(value).await
+    }
+
+    pub async fn rail_enabled(&mut self, rail: Rail) -> Result<bool, Error<I2C::Error>> {
+        Ok(self.power_control().await?.contains(rail.flag()))
+    }
+
+    /// In millivolts, see the blocking `set_dcdc2_voltage_mv()`
+    pub async fn set_dcdc2_voltage_mv(&mut self, mv: u16) -> Result<(), Error<I2C::Error>> {
+        let steps = conversions::dcdc2_to_register(mv);
+        self.modify_register(Registers::Dcdc2Voltage as u8, |value| (value & 0xc0) | steps).await
+    }
+
+    pub async fn dcdc2_voltage_mv(&mut self) -> Result<u16, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::Dcdc2Voltage as u8).await?;
+
+        Ok(conversions::dcdc2_voltage_mv(value))
+    }
+
+    /// In millivolts, see the blocking `set_dcdc3_voltage_mv()`
+    pub async fn set_dcdc3_voltage_mv(&mut self, mv: u16) -> Result<(), Error<I2C::Error>> {
+        let steps = conversions::dcdc3_to_register(mv);
+        self.modify_register(Registers::Dcdc3Voltage as u8, |value| (value & 0x80) | steps).await
+    }
+
+    pub async fn dcdc3_voltage_mv(&mut self) -> Result<u16, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::Dcdc3Voltage as u8).await?;
+
+        Ok(conversions::dcdc3_voltage_mv(value))
+    }
+
+    /// In millivolts, see the blocking `set_ldo2_voltage_mv()`
+    pub async fn set_ldo2_voltage_mv(&mut self, mv: u16) -> Result<(), Error<I2C::Error>> {
+        let steps = conversions::ldo2_to_register(mv);
+        self.modify_register(Registers::Ldo24Voltage as u8, |value| (value & 0x0f) | steps).await
+    }
+
+    pub async fn ldo2_voltage_mv(&mut self) -> Result<u16, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::Ldo24Voltage as u8).await?;
+
+        Ok(conversions::ldo2_voltage_mv(value))
+    }
+
+    /// In millivolts, see the blocking `set_ldo3_voltage_mv()`
+    pub async fn set_ldo3_voltage_mv(&mut self, mv: u16) -> Result<(), Error<I2C::Error>> {
+        let steps = conversions::ldo3_to_register(mv);
+        self.modify_register(Registers::Ldo3Voltage as u8, |value| (value & 0x80) | steps).await
+    }
+
+    pub async fn ldo3_voltage_mv(&mut self) -> Result<u16, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::Ldo3Voltage as u8).await?;
+
+        Ok(conversions::ldo3_voltage_mv(value))
+    }
+
+    pub async fn set_charge_target_voltage(&mut self, voltage: ChargeTargetVoltage) -> Result<(), Error<I2C::Error>> {
+        self.modify_register(Registers::ChargeControl as u8, |value| (value & !0x60) | voltage.to_register()).await
+    }
+
+    pub async fn charge_target_voltage(&mut self) -> Result<ChargeTargetVoltage, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::ChargeControl as u8).await?;
+
+        Ok(ChargeTargetVoltage::from_register(value))
+    }
+
+    /// In milliamps, see the blocking `set_charge_current_ma()`
+    pub async fn set_charge_current_ma(&mut self, ma: u16) -> Result<(), Error<I2C::Error>> {
+        let steps = conversions::charge_current_to_register(ma);
+        self.modify_register(Registers::ChargeControl as u8, |value| (value & 0xf0) | steps).await
+    }
+
+    pub async fn charge_current_ma(&mut self) -> Result<u16, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::ChargeControl as u8).await?;
+
+        Ok(conversions::charge_current_ma(value))
+    }
+
+    pub async fn set_charging_enabled(&mut self, on: bool) -> Result<(), Error<I2C::Error>> {
+        self.modify_register(Registers::ChargeControl as u8, |value| {
+            if on { value | 0x80 } else { value & !0x80 }
+        }).await
+    }
+
+    pub async fn charging_enabled(&mut self) -> Result<bool, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::ChargeControl as u8).await?;
+
+        Ok(value & 0x80 != 0)
+    }
+
+    /// Powers off the whole system, right now
+    pub async fn shutdown(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.modify_register(Registers::ShutdownControl as u8, |value| value | 0x80).await
+    }
+
+    pub async fn adc_control(&mut self) -> Result<AdcControl, Error<I2C::Error>> {
+        let mut buf: [u8; 2] = [0, 0];
+
+        self.get_8bit_registers(Registers::AdcControl as u8, &mut buf).await?;
+
+        Ok(AdcControl::new(u16::from_be_bytes(buf)))
+    }
+
+    pub async fn set_adc_control(&mut self, value: AdcControl) -> Result<(), Error<I2C::Error>> {
+        self.set_8bit_registers(Registers::AdcControl as u8, &value.bits().to_be_bytes()).await
+    }
+
+    pub async fn irq_enable(&mut self) -> Result<IrqEnable, Error<I2C::Error>> {
+        let mut buf: [u8; 5] = [0; 5];
+
+        self.get_8bit_registers(Registers::IrqEnable as u8, &mut buf).await?;
+
+        Ok(IrqEnable::from_registers(buf))
+    }
+
+    /// Writes all five IRQ enable registers
+    pub async fn set_irq_enable(&mut self, value: IrqEnable) -> Result<(), Error<I2C::Error>> {
+        self.set_8bit_registers(Registers::IrqEnable as u8, &value.to_registers()).await
+    }
+
+    /// Reads all five IRQ status registers in one transaction
+    pub async fn irq_status(&mut self) -> Result<IrqStatus, Error<I2C::Error>> {
+        let mut buf: [u8; 5] = [0; 5];
+
+        self.get_8bit_registers(Registers::IrqStatus as u8, &mut buf).await?;
+
+        Ok(IrqStatus::from_registers(buf))
+    }
+
+    /// Acknowledges every pending IRQ
+    pub async fn clear_all(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.set_8bit_registers(Registers::IrqStatus as u8, &[0xff; 5]).await
+    }
+
+    /// In millivolts
+    pub async fn battery_voltage(&mut self) -> Result<Millivolts, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::BatteryVoltage).await?;
+
+        Ok(Millivolts(conversions::battery_voltage(value)))
+    }
+
+    /// In milliamps
+    pub async fn battery_charging_current(&mut self) -> Result<Milliamps, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::BatteryChargeCurrent).await?;
+
+        Ok(Milliamps(conversions::battery_current(value)))
+    }
+
+    /// In milliamps
+    pub async fn battery_discharging_current(&mut self) -> Result<Milliamps, Error<I2C::Error>> {
+        let mut recv: [u8; 2] = [0, 0];
+
+        self.get_8bit_registers(Registers::BatteryDischargeCurrent as u8, &mut recv).await?;
+
+        Ok(Milliamps(conversions::battery_current(conversions::adc_13bits(&recv))))
+    }
+
+    /// In millivolts
+    pub async fn acin_voltage(&mut self) -> Result<Millivolts, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::AcinVoltage).await?;
+
+        Ok(Millivolts(conversions::acin_voltage(value)))
+    }
+
+    /// In milliamps
+    pub async fn acin_current(&mut self) -> Result<Milliamps, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::AcinCurrent).await?;
+
+        Ok(Milliamps(conversions::acin_current(value)))
+    }
+
+    /// In millivolts
+    pub async fn vbus_voltage(&mut self) -> Result<Millivolts, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::VbusVoltage).await?;
+
+        Ok(Millivolts(conversions::vbus_voltage(value)))
+    }
+
+    /// In milliamps
+    pub async fn vbus_current(&mut self) -> Result<Milliamps, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::VbusCurrent).await?;
+
+        Ok(Milliamps(conversions::vbus_current(value)))
+    }
+
+    /// In celcius, rounded towards zero
+    pub async fn temperature(&mut self) -> Result<Celsius, Error<I2C::Error>> {
+        Ok(Celsius((self.temperature_millicelsius().await? / 1000) as i16))
+    }
+
+    /// In thousandths of a degree celcius
+    pub async fn temperature_millicelsius(&mut self) -> Result<i32, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::Temperature).await?;
+
+        Ok(conversions::temperature_millicelsius(value))
+    }
+
+    /// In millivolts. Battery temperature sensor
+    pub async fn ts_voltage(&mut self) -> Result<Millivolts, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::BatteryTemperature).await?;
+
+        Ok(Millivolts(conversions::ts_voltage(value)))
+    }
+
+    /// In millivolts
+    pub async fn ipsout_voltage(&mut self) -> Result<Millivolts, Error<I2C::Error>> {
+        let value = self.get_adc_12bits(Registers::SystemIpsout).await?;
+
+        Ok(Millivolts(conversions::ipsout_voltage(value)))
+    }
+
+    /// Everything the ADC measures, see the blocking `measurements()`
+    pub async fn measurements(&mut self) -> Result<Measurements, Error<I2C::Error>> {
+        let mut power: [u8; 10] = [0; 10];
+        self.get_8bit_registers(Registers::AcinVoltage as u8, &mut power).await?;
+
+        let mut battery: [u8; 6] = [0; 6];
+        self.get_8bit_registers(Registers::BatteryVoltage as u8, &mut battery).await?;
+
+        let level = self.battery_level().await?;
+
+        Ok(conversions::measurements(&power, &battery, level))
+    }
+
+    /// In percentage, or `BATTERY_LEVEL_MISSING` without a battery
+    pub async fn battery_level(&mut self) -> Result<u8, Error<I2C::Error>> {
+        let value = self.get_8bit_register(Registers::BatteryLevel as u8).await?;
+
+        conversions::battery_level(value).ok_or(Error::InvalidData)
+    }
+
+    /// `None` without a battery
+    pub async fn battery_level_opt(&mut self) -> Result<Option<u8>, Error<I2C::Error>> {
+        let level = self.battery_level().await?;
+
+        Ok(Some(level).filter(|&level| level != BATTERY_LEVEL_MISSING))
+    }
+
+    pub async fn battery_present(&mut self) -> Result<bool, Error<I2C::Error>> {
+        Ok(self.battery_level().await? != BATTERY_LEVEL_MISSING)
+    }
+}
@@ -0,0 +1,52 @@
+//! The backup battery register (0x35) controls charging of the coin cell
+//! (or supercap) that keeps the RTC going while the main power is off.
+
+/// The voltage the backup battery gets charged up to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackupBatteryVoltage {
+    V3_1 = 0b00,
+    /// The datasheet lists 3.0V twice, this is the first of them
+    V3_0 = 0b01,
+    V2_5 = 0b11,
+}
+
+/// How much current is used to charge the backup battery
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackupBatteryCurrent {
+    Ua50 = 0b00,
+    Ua100 = 0b01,
+    Ua200 = 0b10,
+    Ua400 = 0b11,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BackupBatteryConfig {
+    pub enabled: bool,
+    pub voltage: BackupBatteryVoltage,
+    pub current: BackupBatteryCurrent,
+}
+
+impl BackupBatteryConfig {
+    /// Decodes register 0x35, ignoring the reserved bits 4-2
+    pub fn from_register(value: u8) -> Self {
+        Self {
+            enabled: value & 0x80 != 0,
+            voltage: match (value >> 5) & 0b11 {
+                0b00 => BackupBatteryVoltage::V3_1,
+                0b11 => BackupBatteryVoltage::V2_5,
+                _ => BackupBatteryVoltage::V3_0,
+            },
+            current: match value & 0b11 {
+                0b00 => BackupBatteryCurrent::Ua50,
+                0b01 => BackupBatteryCurrent::Ua100,
+                0b10 => BackupBatteryCurrent::Ua200,
+                _ => BackupBatteryCurrent::Ua400,
+            },
+        }
+    }
+
+    /// Register 0x35 with the reserved bits left clear
+    pub fn to_register(&self) -> u8 {
+        ((self.enabled as u8) << 7) | ((self.voltage as u8) << 5) | self.current as u8
+    }
+}
@@ -0,0 +1,101 @@
+//! Per-channel corrections for boards whose ADC readings are off by a
+//! consistent amount. Each channel is scaled by a fraction and then shifted
+//! by an offset, after the datasheet's own scaling:
+//!
+//! ```ignore
+//!     // This board reads the battery 2% high and 15mV low
+//!     let calibration = Calibration {
+//!         battery_voltage: ChannelCalibration::new(15, 100, 102),
+//!         ..Calibration::default()
+//!     };
+//!
+//!     let mut pmic = Axp209::with_calibration(i2c, calibration);
+//! ```
+//!
+//! The raw readings (`raw_battery_charge_current()` and friends) are never
+//! corrected, and neither is the temperature.
+
+use crate::measurements::{AdcBlock, Measurements};
+use crate::units::{Milliamps, Millivolts};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChannelCalibration {
+    /// In the channel's unit, so millivolts or milliamps
+    pub offset: i32,
+    pub numerator: i32,
+    /// A denominator of 0 is treated as 1
+    pub denominator: i32,
+}
+
+impl ChannelCalibration {
+    /// Leaves readings as they are
+    pub const IDENTITY: ChannelCalibration = ChannelCalibration::new(0, 1, 1);
+
+    pub const fn new(offset: i32, numerator: i32, denominator: i32) -> Self {
+        ChannelCalibration { offset, numerator, denominator }
+    }
+
+    /// Corrects a reading, clamping to what a `u16` can hold
+    pub fn apply(&self, value: u16) -> u16 {
+        let denominator = if self.denominator == 0 { 1 } else { self.denominator } as i64;
+        let value = value as i64 * self.numerator as i64 / denominator + self.offset as i64;
+
+        value.clamp(0, u16::MAX as i64) as u16
+    }
+
+    pub(crate) fn millivolts(&self, value: Millivolts) -> Millivolts {
+        Millivolts(self.apply(value.0))
+    }
+
+    pub(crate) fn milliamps(&self, value: Milliamps) -> Milliamps {
+        Milliamps(self.apply(value.0))
+    }
+}
+
+impl Default for ChannelCalibration {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// A correction for each ADC channel, all identity by default
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Calibration {
+    pub battery_voltage: ChannelCalibration,
+    pub battery_charge_current: ChannelCalibration,
+    pub battery_discharge_current: ChannelCalibration,
+    pub acin_voltage: ChannelCalibration,
+    pub acin_current: ChannelCalibration,
+    pub vbus_voltage: ChannelCalibration,
+    pub vbus_current: ChannelCalibration,
+    pub ts_voltage: ChannelCalibration,
+    pub gpio0_voltage: ChannelCalibration,
+    pub gpio1_voltage: ChannelCalibration,
+    pub ipsout_voltage: ChannelCalibration,
+}
+
+impl Calibration {
+    pub(crate) fn measurements(&self, m: Measurements) -> Measurements {
+        Measurements {
+            battery_voltage: self.battery_voltage.millivolts(m.battery_voltage),
+            battery_charge_current: self.battery_charge_current.milliamps(m.battery_charge_current),
+            battery_discharge_current: self.battery_discharge_current.milliamps(m.battery_discharge_current),
+            acin_voltage: self.acin_voltage.millivolts(m.acin_voltage),
+            acin_current: self.acin_current.milliamps(m.acin_current),
+            vbus_voltage: self.vbus_voltage.millivolts(m.vbus_voltage),
+            vbus_current: self.vbus_current.milliamps(m.vbus_current),
+            ..m
+        }
+    }
+
+    pub(crate) fn adc_block(&self, block: AdcBlock) -> AdcBlock {
+        AdcBlock {
+            acin_voltage: self.acin_voltage.millivolts(block.acin_voltage),
+            acin_current: self.acin_current.milliamps(block.acin_current),
+            vbus_voltage: self.vbus_voltage.millivolts(block.vbus_voltage),
+            vbus_current: self.vbus_current.milliamps(block.vbus_current),
+            ts_voltage: self.ts_voltage.millivolts(block.ts_voltage),
+            ..block
+        }
+    }
+}
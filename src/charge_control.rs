@@ -0,0 +1,82 @@
+//! Charge Control register 1 (0x33) configures how the battery gets
+//! charged: whether it's charged at all, the voltage it's charged up to
+//! and how much current is pushed into it. Charge Control register 2 (0x34)
+//! holds the timeouts that stop a charge that's taking too long.
+
+/// The voltage the battery gets charged up to. Pick the one matching the
+/// battery chemistry, most LiPo packs want 4.2V.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChargeTargetVoltage {
+    V4_10 = 0b00,
+    V4_15 = 0b01,
+    V4_20 = 0b10,
+    V4_36 = 0b11,
+}
+
+impl ChargeTargetVoltage {
+    /// Decodes bits 6-5 of register 0x33
+    pub fn from_register(value: u8) -> Self {
+        match (value >> 5) & 0b11 {
+            0b00 => ChargeTargetVoltage::V4_10,
+            0b01 => ChargeTargetVoltage::V4_15,
+            0b10 => ChargeTargetVoltage::V4_20,
+            _ => ChargeTargetVoltage::V4_36,
+        }
+    }
+
+    /// The voltage as bits 6-5 of register 0x33, the rest left clear
+    pub fn to_register(&self) -> u8 {
+        (*self as u8) << 5
+    }
+}
+
+/// How long the battery can sit in pre-charge (trickle charging a deeply
+/// discharged cell) before the charger gives up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrechargeTimeout {
+    Min40 = 0b00,
+    Min50 = 0b01,
+    Min60 = 0b10,
+    Min70 = 0b11,
+}
+
+/// How long the constant current stage can go on before the charger gives
+/// up. Big packs charged at a low current may need the longer ones.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChargeTimeout {
+    Hours7 = 0b00,
+    Hours8 = 0b01,
+    Hours9 = 0b10,
+    Hours10 = 0b11,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChargeTimeouts {
+    pub precharge: PrechargeTimeout,
+    pub constant_current: ChargeTimeout,
+}
+
+impl ChargeTimeouts {
+    /// Decodes bits 7-6 and 1-0 of register 0x34
+    pub fn from_register(value: u8) -> Self {
+        Self {
+            precharge: match (value >> 6) & 0b11 {
+                0b00 => PrechargeTimeout::Min40,
+                0b01 => PrechargeTimeout::Min50,
+                0b10 => PrechargeTimeout::Min60,
+                _ => PrechargeTimeout::Min70,
+            },
+            constant_current: match value & 0b11 {
+                0b00 => ChargeTimeout::Hours7,
+                0b01 => ChargeTimeout::Hours8,
+                0b10 => ChargeTimeout::Hours9,
+                _ => ChargeTimeout::Hours10,
+            },
+        }
+    }
+
+    /// Both timeouts as bits 7-6 and 1-0 of register 0x34, the rest left clear
+    pub fn to_register(&self) -> u8 {
+        ((self.precharge as u8) << 6) | self.constant_current as u8
+    }
+}
@@ -1,13 +1,18 @@
 bitflags! {
     /// The Charging Status register reports on the state of the battery
     /// and if the chip itself is overtemperature
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct ChargingStatus: u8 {
         /// Whether the chip is at an unsafe temperature
         const OVERTEMPERATURE = 1 << 7;
         /// Whether the battery is charging
         const CHARGING = 1 << 6;
+        /// Whether a battery is connected
         const BATTERY_PRESENT = 1 << 5;
+        /// Whether the battery is in activation mode
         const CELL_ACTIVATION_MODE = 1 << 3;    // "enetered cell activation mode"?!
+        /// Whether the charge current is lower than what was configured
         const CHARGE_CURRENT_LOW = 1 << 2;
     }
 }
@@ -18,4 +23,23 @@ impl ChargingStatus {
             bits: value
         }
     }
-}
\ No newline at end of file
+
+    pub fn over_temperature(&self) -> bool {
+        self.contains(Self::OVERTEMPERATURE)
+    }
+
+    pub fn charging(&self) -> bool {
+        self.contains(Self::CHARGING)
+    }
+
+    /// This is the bit to trust for battery detection, rather than guessing
+    /// from the battery level.
+    pub fn battery_present(&self) -> bool {
+        self.contains(Self::BATTERY_PRESENT)
+    }
+
+    /// Whether the battery has entered activation mode
+    pub fn battery_active(&self) -> bool {
+        self.contains(Self::CELL_ACTIVATION_MODE)
+    }
+}
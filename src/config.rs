@@ -0,0 +1,97 @@
+//! A bundle of settings for bringing up a board in one go, applied with
+//! `Axp209::configure()`. Anything left unset isn't touched:
+//!
+//! ```ignore
+//!     let config = Config::new()
+//!         .charge_target_voltage(ChargeTargetVoltage::V4_20)
+//!         .charge_current_ma(500)
+//!         .dcdc2_voltage_mv(1400)
+//!         .adc_control(AdcControl::all());
+//!
+//!     pmic.configure(&config)?;
+//! ```
+//!
+//! The settings are always applied in the same order: charging first so a
+//! battery is never pushed harder than intended, then the rail voltages,
+//! then the ADC channels.
+
+use crate::adc_control::AdcControl;
+use crate::charge_control::ChargeTargetVoltage;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Config {
+    pub(crate) charge_target_voltage: Option<ChargeTargetVoltage>,
+    pub(crate) charge_current_ma: Option<u16>,
+    pub(crate) dcdc2_voltage_mv: Option<u16>,
+    pub(crate) dcdc3_voltage_mv: Option<u16>,
+    pub(crate) ldo2_voltage_mv: Option<u16>,
+    pub(crate) ldo3_voltage_mv: Option<u16>,
+    pub(crate) adc_control: Option<AdcControl>,
+}
+
+impl Config {
+    /// Starts out with nothing set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn charge_target_voltage(mut self, voltage: ChargeTargetVoltage) -> Self {
+        self.charge_target_voltage = Some(voltage);
+        self
+    }
+
+    /// In milliamps, see `Axp209::set_charge_current_ma()`
+    pub fn charge_current_ma(mut self, ma: u16) -> Self {
+        self.charge_current_ma = Some(ma);
+        self
+    }
+
+    /// In millivolts, see `Axp209::set_dcdc2_voltage_mv()`
+    pub fn dcdc2_voltage_mv(mut self, mv: u16) -> Self {
+        self.dcdc2_voltage_mv = Some(mv);
+        self
+    }
+
+    /// In millivolts, see `Axp209::set_dcdc3_voltage_mv()`
+    pub fn dcdc3_voltage_mv(mut self, mv: u16) -> Self {
+        self.dcdc3_voltage_mv = Some(mv);
+        self
+    }
+
+    /// In millivolts, see `Axp209::set_ldo2_voltage_mv()`
+    pub fn ldo2_voltage_mv(mut self, mv: u16) -> Self {
+        self.ldo2_voltage_mv = Some(mv);
+        self
+    }
+
+    /// In millivolts, see `Axp209::set_ldo3_voltage_mv()`
+    pub fn ldo3_voltage_mv(mut self, mv: u16) -> Self {
+        self.ldo3_voltage_mv = Some(mv);
+        self
+    }
+
+    /// Which ADC channels to sample
+    pub fn adc_control(mut self, value: AdcControl) -> Self {
+        self.adc_control = Some(value);
+        self
+    }
+}
+
+/// The AXP209 counts charge but has nowhere to keep the battery's
+/// capacity (later chips in the family do, the AXP209 doesn't), so it's
+/// kept on the driver instead. See `Axp209::set_coulomb_config()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CoulombConfig {
+    rated_capacity_mah: u16,
+}
+
+impl CoulombConfig {
+    pub fn new(rated_capacity_mah: u16) -> Self {
+        CoulombConfig { rated_capacity_mah }
+    }
+
+    /// In milliamp-hours
+    pub fn rated_capacity_mah(&self) -> u16 {
+        self.rated_capacity_mah
+    }
+}
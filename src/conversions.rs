@@ -0,0 +1,288 @@
+//! The math turning raw register values into something useful and back,
+//! kept apart from the bus handling so the blocking and async drivers agree
+//! on it.
+
+use crate::measurements::{AdcBlock, Measurements};
+use crate::units::{Celsius, Milliamps, Millivolts};
+use crate::BATTERY_LEVEL_MISSING;
+
+/// Most ADC values are 12 bits spread over two registers, with only the
+/// low nibble of the second one being used
+pub fn adc_12bits(recv: &[u8]) -> u16 {
+    // Weird way to store a number if ye ask me!
+    ((recv[0] as u16) << 4) | (recv[1] as u16 & 0x0f)
+}
+
+/// The battery discharge current is the odd one out with 13 bits
+pub fn adc_13bits(recv: &[u8]) -> u16 {
+    // Of course one would have 5 least significant bits and
+    // ruin my adc_12bits function above!
+    ((recv[0] as u16) << 5) | (recv[1] as u16 & 0x1f)
+}
+
+/// In microvolts, which the 1.1mV steps fit exactly
+pub fn battery_voltage_uv(raw: u16) -> u32 {
+    raw as u32 * crate::BATTERY_VOLTAGE_LSB_UV
+}
+
+/// In millivolts, rounded to the nearest one
+pub fn battery_voltage(raw: u16) -> u16 {
+    ((battery_voltage_uv(raw) + 500) / 1000) as u16
+}
+
+/// In milliamps, for both the charge and discharge current
+pub fn battery_current(raw: u16) -> u16 {
+    raw / 2
+}
+
+/// In millivolts
+pub fn acin_voltage(raw: u16) -> u16 {
+    // Increments of 1.7mV, and 4095 * 7 still fits in a u16
+    raw + raw * 7 / 10
+}
+
+/// In milliamps
+pub fn acin_current(raw: u16) -> u16 {
+    // Multiples of 0.625 milliamps, same trick as vbus_current()
+    (raw as u32 * crate::ACIN_CURRENT_LSB_UA / 1000) as u16
+}
+
+/// In millivolts
+pub fn vbus_voltage(raw: u16) -> u16 {
+    // Same 1.7mV increments as ACIN
+    raw + raw * 7 / 10
+}
+
+/// In milliamps
+pub fn vbus_current(raw: u16) -> u16 {
+    // Multiples of 0.375 milliamps, done in microamps so a full scale
+    // reading doesn't overflow
+    (raw as u32 * crate::VBUS_CURRENT_LSB_UA / 1000) as u16
+}
+
+/// In thousandths of a degree celcius
+pub fn temperature_millicelsius(raw: u16) -> i32 {
+    // Check out page 25 of the datasheet for the weird math: 0.1C per
+    // step, starting from -144.7C
+    raw as i32 * 100 - 144_700
+}
+
+/// In celcius
+pub fn temperature(raw: u16) -> i16 {
+    (temperature_millicelsius(raw) / 1000) as i16
+}
+
+/// In millivolts
+pub fn ts_voltage(raw: u16) -> u16 {
+    // Increments of 0.8
+    (raw * 8) / 10
+}
+
+/// Ohm's law on the TS pin voltage and the current pushed through it, or
+/// `None` with no current
+pub fn ts_resistance_ohms(raw: u16, current_ua: u16) -> Option<u32> {
+    if current_ua == 0 {
+        return None;
+    }
+
+    // Microvolts over microamps is ohms
+    Some(raw as u32 * crate::TS_VOLTAGE_LSB_UV / current_ua as u32)
+}
+
+/// In millivolts
+pub fn ipsout_voltage(raw: u16) -> u16 {
+    // Increments of 1.4
+    (raw * 14) / 10
+}
+
+/// In millivolts. The GPIO ADC can either measure from 0V or from 0.7V,
+/// depending on the range picked for the pin.
+pub fn gpio_voltage(raw: u16, offset: bool) -> u16 {
+    // Increments of 0.5mV
+    let value = raw / 2;
+
+    if offset {
+        value + 700
+    } else {
+        value
+    }
+}
+
+/// TS pin temperature thresholds are in steps of 12.8mV (16 steps of the
+/// 0.8mV ADC), rounded to the nearest step and capped at 3264mV.
+pub fn ts_threshold_to_register(mv: u16) -> u8 {
+    let value = (mv as u32 * 10 + 64) / 128;
+
+    if value > 0xff { 0xff } else { value as u8 }
+}
+
+/// In millivolts, rounded down
+pub fn ts_threshold_mv(value: u8) -> u16 {
+    (value as u32 * 128 / 10) as u16
+}
+
+/// The APS warning levels start at 2867.2mV and go up in 5.6mV steps
+/// (1.4mV * 4). Everything is done in tenths of a millivolt to keep the
+/// fractions, rounding to the nearest step.
+pub fn aps_warning_to_register(mv: u16) -> u8 {
+    let tenths = mv as u32 * 10;
+
+    if tenths <= 28_672 {
+        return 0;
+    }
+
+    let value = (tenths - 28_672 + 28) / 56;
+
+    if value > 0xff { 0xff } else { value as u8 }
+}
+
+/// In millivolts, rounded down
+pub fn aps_warning_mv(value: u8) -> u16 {
+    ((28_672 + value as u32 * 56) / 10) as u16
+}
+
+/// Clamps `value` to `min..=max` and rounds it to the nearest whole step
+/// above `min`, which is how all the adjustable voltages and currents are
+/// encoded.
+pub fn clamp_to_steps(value: u16, min: u16, max: u16, step: u16) -> u8 {
+    let value = if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    };
+
+    ((value - min + step / 2) / step) as u8
+}
+
+/// 700mV - 2275mV in 25mV steps, bits [5:0]
+pub fn dcdc2_to_register(mv: u16) -> u8 {
+    clamp_to_steps(mv, 700, 2275, 25)
+}
+
+pub fn dcdc2_voltage_mv(value: u8) -> u16 {
+    700 + (value & 0x3f) as u16 * 25
+}
+
+/// 700mV - 3500mV in 25mV steps, bits [6:0]
+pub fn dcdc3_to_register(mv: u16) -> u8 {
+    clamp_to_steps(mv, 700, 3500, 25)
+}
+
+pub fn dcdc3_voltage_mv(value: u8) -> u16 {
+    700 + (value & 0x7f) as u16 * 25
+}
+
+/// 1800mV - 3300mV in 100mV steps, bits [7:4]
+pub fn ldo2_to_register(mv: u16) -> u8 {
+    clamp_to_steps(mv, 1800, 3300, 100) << 4
+}
+
+pub fn ldo2_voltage_mv(value: u8) -> u16 {
+    1800 + (value >> 4) as u16 * 100
+}
+
+/// Same as DCDC3
+pub fn ldo3_to_register(mv: u16) -> u8 {
+    dcdc3_to_register(mv)
+}
+
+pub fn ldo3_voltage_mv(value: u8) -> u16 {
+    dcdc3_voltage_mv(value)
+}
+
+/// 2600mV - 3300mV in 100mV steps, bits [2:0]
+pub fn voff_to_register(mv: u16) -> u8 {
+    clamp_to_steps(mv, 2600, 3300, 100)
+}
+
+pub fn voff_voltage_mv(value: u8) -> u16 {
+    2600 + (value & 0b111) as u16 * 100
+}
+
+/// 300mA - 1800mA in 100mA steps, bits [3:0]
+pub fn charge_current_to_register(ma: u16) -> u8 {
+    clamp_to_steps(ma, 300, 1800, 100)
+}
+
+pub fn charge_current_ma(value: u8) -> u16 {
+    300 + (value & 0x0f) as u16 * 100
+}
+
+/// In percentage, or `BATTERY_LEVEL_MISSING`. Anything else above 100% is
+/// nonsense and comes back as `None`.
+pub fn battery_level(value: u8) -> Option<u8> {
+    // The MSB for the voltage is a control bit that enables or
+    // disables sampling
+    let level = value & 0b0111_1111;
+
+    if level > 100 && level != BATTERY_LEVEL_MISSING {
+        None
+    } else {
+        Some(level)
+    }
+}
+
+/// Puts together `Measurements` from the ten bytes starting at ACIN voltage
+/// (0x56) and the six starting at battery voltage (0x78)
+pub fn measurements(power: &[u8; 10], battery: &[u8; 6], battery_level: u8) -> Measurements {
+    Measurements {
+        battery_voltage: Millivolts(battery_voltage(adc_12bits(&battery[0..2]))),
+        battery_charge_current: Milliamps(battery_current(adc_12bits(&battery[2..4]))),
+        battery_discharge_current: Milliamps(battery_current(adc_13bits(&battery[4..6]))),
+        acin_voltage: Millivolts(acin_voltage(adc_12bits(&power[0..2]))),
+        acin_current: Milliamps(acin_current(adc_12bits(&power[2..4]))),
+        vbus_voltage: Millivolts(vbus_voltage(adc_12bits(&power[4..6]))),
+        vbus_current: Milliamps(vbus_current(adc_12bits(&power[6..8]))),
+        temperature: Celsius(temperature(adc_12bits(&power[8..10]))),
+        battery_level,
+    }
+}
+
+/// Decodes the registers from 0x56 to 0x63. 0x60 and 0x61 are reserved and
+/// skipped.
+pub fn adc_block(recv: &[u8; 14]) -> AdcBlock {
+    AdcBlock {
+        acin_voltage: Millivolts(acin_voltage(adc_12bits(&recv[0..2]))),
+        acin_current: Milliamps(acin_current(adc_12bits(&recv[2..4]))),
+        vbus_voltage: Millivolts(vbus_voltage(adc_12bits(&recv[4..6]))),
+        vbus_current: Milliamps(vbus_current(adc_12bits(&recv[6..8]))),
+        temperature: Celsius(temperature(adc_12bits(&recv[8..10]))),
+        ts_voltage: Millivolts(ts_voltage(adc_12bits(&recv[12..14]))),
+    }
+}
+
+/// Linearly interpolates a percentage out of a millivolt to percent curve
+/// sorted by voltage, clamping to the first and last points. `None` when
+/// the curve is empty.
+pub fn soc_from_voltage(mv: u16, curve: &[(u16, u8)]) -> Option<u8> {
+    let &(first_mv, first_percent) = curve.first()?;
+    let &(last_mv, last_percent) = curve.last()?;
+
+    if mv <= first_mv {
+        return Some(first_percent);
+    }
+    if mv >= last_mv {
+        return Some(last_percent);
+    }
+
+    for points in curve.windows(2) {
+        let (low_mv, low_percent) = points[0];
+        let (high_mv, high_percent) = points[1];
+
+        if mv <= high_mv {
+            let span = high_mv.saturating_sub(low_mv) as i32;
+            if span == 0 {
+                return Some(high_percent);
+            }
+
+            let offset = mv.saturating_sub(low_mv) as i32;
+            let percent = low_percent as i32 + (high_percent as i32 - low_percent as i32) * offset / span;
+
+            return Some(percent as u8);
+        }
+    }
+
+    Some(last_percent)
+}
@@ -0,0 +1,31 @@
+//! The coulomb counter control register (0xb8). Only the top three bits
+//! do anything, the rest are reserved.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CoulombControl {
+    /// Counting at all
+    pub enabled: bool,
+    /// Suspended, keeping the accumulators as they are
+    pub paused: bool,
+    /// Clearing both accumulators. The chip drops this bit once it's done.
+    pub clearing: bool,
+}
+
+impl CoulombControl {
+    /// The bits of the register covered here
+    pub const MASK: u8 = 0xe0;
+
+    /// Decodes bits 7 (enable), 6 (pause) and 5 (clear) of register 0xb8
+    pub fn from_register(value: u8) -> Self {
+        CoulombControl {
+            enabled: value & 0x80 != 0,
+            paused: value & 0x40 != 0,
+            clearing: value & 0x20 != 0,
+        }
+    }
+
+    /// The settings as bits 7-5 of register 0xb8, the reserved bits left clear
+    pub fn to_register(&self) -> u8 {
+        ((self.enabled as u8) << 7) | ((self.paused as u8) << 6) | ((self.clearing as u8) << 5)
+    }
+}
@@ -0,0 +1,101 @@
+//! Fixed point versions of the ADC readers, behind the `fixed` feature, for
+//! anyone who wants the fractions of a millivolt the integer readers round
+//! away without pulling in floats.
+//!
+//! Everything is in the same unit as the integer reader (millivolts,
+//! milliamps or degrees celcius) with 16 fractional bits, worked out from
+//! the exact step sizes. Calibration isn't applied.
+
+use fixed::types::I16F16;
+use hal::i2c::I2c;
+
+use crate::{Axp209, Error, Registers};
+use crate::{
+    ACIN_CURRENT_LSB_UA, ACIN_VOLTAGE_LSB_UV, BATTERY_CURRENT_LSB_UA, BATTERY_VOLTAGE_LSB_UV,
+    IPSOUT_VOLTAGE_LSB_UV, TS_VOLTAGE_LSB_UV, VBUS_CURRENT_LSB_UA, VBUS_VOLTAGE_LSB_UV,
+};
+
+/// Thousandths of a unit to 16.16 fixed point. The largest reading (ACIN at
+/// just under 7V) is nowhere near I16F16's limit of 32767.
+pub(crate) fn from_thousandths(value: i64) -> I16F16 {
+    I16F16::from_bits((value * (1 << 16) / 1000) as i32)
+}
+
+/// A raw reading times its step size, in thousandths of a millivolt or
+/// milliamp
+pub(crate) fn scale(raw: u16, lsb: u32) -> I16F16 {
+    from_thousandths(raw as i64 * lsb as i64)
+}
+
+impl<I2C, E> Axp209<I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    /// In millivolts
+    pub fn battery_voltage_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.get_adc_12bits(Registers::BatteryVoltage as u8)?;
+
+        Ok(scale(value, BATTERY_VOLTAGE_LSB_UV))
+    }
+
+    /// In milliamps
+    pub fn battery_charging_current_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.raw_battery_charge_current()?;
+
+        Ok(scale(value, BATTERY_CURRENT_LSB_UA))
+    }
+
+    /// In milliamps
+    pub fn battery_discharging_current_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.raw_battery_discharge_current()?;
+
+        Ok(scale(value, BATTERY_CURRENT_LSB_UA))
+    }
+
+    /// In millivolts
+    pub fn acin_voltage_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.get_adc_12bits(Registers::AcinVoltage as u8)?;
+
+        Ok(scale(value, ACIN_VOLTAGE_LSB_UV))
+    }
+
+    /// In milliamps
+    pub fn acin_current_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.get_adc_12bits(Registers::AcinCurrent as u8)?;
+
+        Ok(scale(value, ACIN_CURRENT_LSB_UA))
+    }
+
+    /// In millivolts
+    pub fn vbus_voltage_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.get_adc_12bits(Registers::VbusVoltage as u8)?;
+
+        Ok(scale(value, VBUS_VOLTAGE_LSB_UV))
+    }
+
+    /// In milliamps
+    pub fn vbus_current_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.get_adc_12bits(Registers::VbusCurrent as u8)?;
+
+        Ok(scale(value, VBUS_CURRENT_LSB_UA))
+    }
+
+    /// In millivolts
+    pub fn ts_voltage_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.get_adc_12bits(Registers::BatteryTemperature as u8)?;
+
+        Ok(scale(value, TS_VOLTAGE_LSB_UV))
+    }
+
+    /// In millivolts
+    pub fn ipsout_voltage_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        let value = self.get_adc_12bits(Registers::SystemIpsout as u8)?;
+
+        Ok(scale(value, IPSOUT_VOLTAGE_LSB_UV))
+    }
+
+    /// In degrees celcius
+    pub fn temperature_fixed(&mut self) -> Result<I16F16, Error<E>> {
+        Ok(from_thousandths(self.temperature_millicelsius()? as i64))
+    }
+}
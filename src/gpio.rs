@@ -0,0 +1,132 @@
+//! The AXP209 has four GPIO pins, each configured through its own control
+//! register. They don't all support the same functions: only GPIO0 and
+//! GPIO1 can be an ADC input or an LDO, and GPIO2 and GPIO3 can only sink
+//! current when used as an output.
+
+use crate::Registers;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gpio {
+    Gpio0,
+    Gpio1,
+    Gpio2,
+    Gpio3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GpioMode {
+    /// Driven low
+    OutputLow,
+    /// Driven high (GPIO0 and GPIO1 only)
+    OutputHigh,
+    /// Not driven at all. As an output on GPIO2 and GPIO3 this is how "high"
+    /// is done, with a pull-up on the board.
+    Floating,
+    Input,
+    /// Low noise LDO output (GPIO0 only)
+    Ldo,
+    /// Voltage is sampled by the ADC (GPIO0 and GPIO1 only)
+    Adc,
+}
+
+/// What the GPIO ADC measures from, set per pin in register 0x85. Both
+/// ranges are the same 2.0475V wide.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GpioAdcRange {
+    /// 0V - 2.0475V
+    Low,
+    /// 0.7V - 2.7475V
+    High,
+}
+
+impl Gpio {
+    /// The register holding this pin's function
+    pub fn control_register(&self) -> Registers {
+        match *self {
+            Gpio::Gpio0 => Registers::Gpio0Control,
+            Gpio::Gpio1 => Registers::Gpio1Control,
+            Gpio::Gpio2 => Registers::Gpio2Control,
+            Gpio::Gpio3 => Registers::Gpio3Control,
+        }
+    }
+
+    /// Where the pin's input state can be read from, and the bit it's in.
+    /// GPIO0 to GPIO2 share a register, GPIO3 has it in its control register.
+    pub fn input_state(&self) -> (Registers, u8) {
+        match *self {
+            Gpio::Gpio0 => (Registers::GpioState, 1 << 4),
+            Gpio::Gpio1 => (Registers::GpioState, 1 << 5),
+            Gpio::Gpio2 => (Registers::GpioState, 1 << 6),
+            Gpio::Gpio3 => (Registers::Gpio3Control, 1 << 0),
+        }
+    }
+
+    /// The output mode that drives the pin high, or lets it float for the
+    /// pins that can only sink current
+    pub fn output_mode(&self, high: bool) -> GpioMode {
+        match (*self, high) {
+            (_, false) => GpioMode::OutputLow,
+            (Gpio::Gpio0, true) | (Gpio::Gpio1, true) => GpioMode::OutputHigh,
+            (_, true) => GpioMode::Floating,
+        }
+    }
+
+    /// The bit of the GPIO ADC range register for this pin, or `None` for
+    /// the pins the ADC can't sample
+    pub fn adc_range_bit(&self) -> Option<u8> {
+        match *self {
+            Gpio::Gpio0 => Some(1 << 0),
+            Gpio::Gpio1 => Some(1 << 1),
+            _ => None,
+        }
+    }
+
+    /// The bits of the control register holding the function
+    pub fn mode_mask(&self) -> u8 {
+        match *self {
+            Gpio::Gpio3 => 0b110,
+            _ => 0b111,
+        }
+    }
+
+    /// The function bits for `mode` on this pin, or `None` if the pin can't
+    /// do that
+    pub fn encode_mode(&self, mode: GpioMode) -> Option<u8> {
+        match (*self, mode) {
+            (Gpio::Gpio3, GpioMode::OutputLow) => Some(0b000),
+            (Gpio::Gpio3, GpioMode::Floating) => Some(0b010),
+            (Gpio::Gpio3, GpioMode::Input) => Some(0b100),
+            (Gpio::Gpio3, _) => None,
+            (Gpio::Gpio2, GpioMode::OutputLow) => Some(0b000),
+            (Gpio::Gpio2, GpioMode::Floating) => Some(0b001),
+            (Gpio::Gpio2, GpioMode::Input) => Some(0b010),
+            (Gpio::Gpio2, _) => None,
+            (Gpio::Gpio1, GpioMode::Ldo) => None,
+            (_, GpioMode::OutputLow) => Some(0b000),
+            (_, GpioMode::OutputHigh) => Some(0b001),
+            (_, GpioMode::Input) => Some(0b010),
+            (_, GpioMode::Ldo) => Some(0b011),
+            (_, GpioMode::Adc) => Some(0b100),
+            (_, GpioMode::Floating) => Some(0b110),
+        }
+    }
+
+    /// Decodes the function out of this pin's control register
+    pub fn decode_mode(&self, value: u8) -> GpioMode {
+        match (*self, value & self.mode_mask()) {
+            (Gpio::Gpio3, 0b000) => GpioMode::OutputLow,
+            (Gpio::Gpio3, 0b010) => GpioMode::Floating,
+            (Gpio::Gpio3, _) => GpioMode::Input,
+            (Gpio::Gpio2, 0b000) => GpioMode::OutputLow,
+            (Gpio::Gpio2, 0b001) => GpioMode::Floating,
+            (Gpio::Gpio2, 0b010) => GpioMode::Input,
+            (Gpio::Gpio2, _) => GpioMode::Floating,
+            (_, 0b000) | (_, 0b101) => GpioMode::OutputLow,
+            (_, 0b001) => GpioMode::OutputHigh,
+            (_, 0b010) => GpioMode::Input,
+            (_, 0b011) => GpioMode::Ldo,
+            (_, 0b100) => GpioMode::Adc,
+            (_, _) => GpioMode::Floating,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! The IRQ Enable registers (0x40 - 0x44) pick which events pull the IRQ
+//! pin low. They share their layout with the IRQ Status registers so the
+//! events are named using the `IrqStatus` flags.
+//!
+//! Like the other registers, changes need to be committed manually:
+//!
+//! ```ignore
+//!     let irqs = IrqEnable::new()
+//!         .enable(IrqStatus::PEK_SHORT_PRESS)
+//!         .enable(IrqStatus::APS_LOW_LEVEL1);
+//!
+//!     pmic.set_irq_enable(irqs);
+//! ```
+
+use crate::irq_status::IrqStatus;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IrqEnable {
+    events: IrqStatus,
+}
+
+impl IrqEnable {
+    /// Starts out with every event disabled
+    pub fn new() -> Self {
+        Self {
+            events: IrqStatus::empty(),
+        }
+    }
+
+    /// Unpacks the five registers, starting at 0x40
+    pub fn from_registers(registers: [u8; 5]) -> Self {
+        Self {
+            events: IrqStatus::from_registers(registers),
+        }
+    }
+
+    /// Splits the enabled events back out into the five registers, starting at 0x40
+    pub fn to_registers(&self) -> [u8; 5] {
+        self.events.to_registers()
+    }
+
+    /// Have the given event(s) raise the IRQ pin
+    pub fn enable(mut self, events: IrqStatus) -> Self {
+        self.events.insert(events);
+        self
+    }
+
+    /// Stop the given event(s) from raising the IRQ pin
+    pub fn disable(mut self, events: IrqStatus) -> Self {
+        self.events.remove(events);
+        self
+    }
+
+    /// Whether all of the given event(s) will raise the IRQ pin
+    pub fn enabled(&self, events: IrqStatus) -> bool {
+        self.events.contains(events)
+    }
+
+    /// Every event that will raise the IRQ pin
+    pub fn events(&self) -> IrqStatus {
+        self.events
+    }
+}
+
+impl Default for IrqEnable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,334 @@
+//! The IRQ Status registers (0x48 - 0x4c) latch every event the chip can
+//! raise an interrupt for, whether or not the IRQ pin is enabled for it.
+//! A bit stays set until it's acknowledged by writing a 1 back to it.
+//!
+//! The five registers are read in one go and packed into a single number
+//! with register 0x48 in the most significant byte, so 0x4c's bits are 0 - 7,
+//! 0x4b's are 8 - 15 and so on.
+
+bitflags! {
+    /// Holds the state of all five registers.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct IrqStatus: u64 {
+        // Register 0x48
+        /// ACIN went over voltage
+        const ACIN_OVERVOLTAGE = 1 << 39;
+        /// Power was plugged into ACIN
+        const ACIN_CONNECTED = 1 << 38;
+        /// Power was removed from ACIN
+        const ACIN_REMOVED = 1 << 37;
+        /// VBUS went over voltage
+        const VBUS_OVERVOLTAGE = 1 << 36;
+        /// Power was plugged into VBUS
+        const VBUS_CONNECTED = 1 << 35;
+        /// Power was removed from VBUS
+        const VBUS_REMOVED = 1 << 34;
+        /// VBUS is usable but below the configured VHOLD voltage
+        const VBUS_BELOW_HOLD = 1 << 33;
+        // Register 0x49
+        /// A battery was connected
+        const BATTERY_CONNECTED = 1 << 31;
+        /// The battery was removed
+        const BATTERY_REMOVED = 1 << 30;
+        /// The battery entered activation mode
+        const BATTERY_ACTIVATION_ENTERED = 1 << 29;
+        /// The battery left activation mode
+        const BATTERY_ACTIVATION_EXITED = 1 << 28;
+        /// The battery started charging
+        const CHARGING_STARTED = 1 << 27;
+        /// The battery finished charging
+        const CHARGING_FINISHED = 1 << 26;
+        /// The battery is too hot
+        const BATTERY_OVERTEMPERATURE = 1 << 25;
+        /// The battery is too cold
+        const BATTERY_UNDERTEMPERATURE = 1 << 24;
+        // Register 0x4a
+        /// The chip itself is too hot
+        const OVERTEMPERATURE = 1 << 23;
+        /// The charge current is lower than what was configured
+        const CHARGE_CURRENT_LOW = 1 << 22;
+        /// DCDC1 output voltage is too low
+        const DCDC1_UNDERVOLTAGE = 1 << 21;
+        /// DCDC2 output voltage is too low
+        const DCDC2_UNDERVOLTAGE = 1 << 20;
+        /// DCDC3 output voltage is too low
+        const DCDC3_UNDERVOLTAGE = 1 << 19;
+        /// The power key was pressed briefly
+        const PEK_SHORT_PRESS = 1 << 17;
+        /// The power key was held down
+        const PEK_LONG_PRESS = 1 << 16;
+        // Register 0x4b
+        /// The N_OE pin asked to power on
+        const NOE_POWER_ON = 1 << 15;
+        /// The N_OE pin asked to power off
+        const NOE_POWER_OFF = 1 << 14;
+        /// VBUS became valid
+        const VBUS_VALID = 1 << 13;
+        /// VBUS stopped being valid
+        const VBUS_INVALID = 1 << 12;
+        /// The VBUS session became valid (USB OTG)
+        const VBUS_SESSION_VALID = 1 << 11;
+        /// The VBUS session ended (USB OTG)
+        const VBUS_SESSION_END = 1 << 10;
+        /// The system voltage dropped below warning level 1
+        const APS_LOW_LEVEL1 = 1 << 9;
+        /// The system voltage dropped below warning level 2
+        const APS_LOW_LEVEL2 = 1 << 8;
+        // Register 0x4c
+        /// The countdown timer expired
+        const TIMER_EXPIRED = 1 << 7;
+        /// The power key was released
+        const PEK_RISING_EDGE = 1 << 6;
+        /// The power key was pressed
+        const PEK_FALLING_EDGE = 1 << 5;
+        /// Input edge on GPIO3
+        const GPIO3_EDGE = 1 << 3;
+        /// Input edge on GPIO2
+        const GPIO2_EDGE = 1 << 2;
+        /// Input edge on GPIO1
+        const GPIO1_EDGE = 1 << 1;
+        /// Input edge on GPIO0
+        const GPIO0_EDGE = 1 << 0;
+    }
+}
+
+impl IrqStatus {
+    /// No checks are made here, and it's expected that it be populated by
+    /// the raw value from the axp209 chip.
+    pub fn new(value: u64) -> Self {
+        Self {
+            bits: value
+        }
+    }
+
+    /// Packs the five registers, starting at 0x48, into a single value
+    pub fn from_registers(registers: [u8; 5]) -> Self {
+        let mut value: u64 = 0;
+        for register in registers.iter() {
+            value = (value << 8) | *register as u64;
+        }
+
+        Self::new(value)
+    }
+
+    /// Splits the value back out into the five registers, starting at 0x48
+    pub fn to_registers(&self) -> [u8; 5] {
+        let mut registers = [0; 5];
+        for (i, register) in registers.iter_mut().enumerate() {
+            *register = (self.bits >> ((4 - i) * 8)) as u8;
+        }
+
+        registers
+    }
+
+
+    pub fn acin_overvoltage(&self) -> bool {
+        self.contains(Self::ACIN_OVERVOLTAGE)
+    }
+
+    pub fn acin_connected(&self) -> bool {
+        self.contains(Self::ACIN_CONNECTED)
+    }
+
+    pub fn acin_removed(&self) -> bool {
+        self.contains(Self::ACIN_REMOVED)
+    }
+
+    pub fn vbus_overvoltage(&self) -> bool {
+        self.contains(Self::VBUS_OVERVOLTAGE)
+    }
+
+    pub fn vbus_connected(&self) -> bool {
+        self.contains(Self::VBUS_CONNECTED)
+    }
+
+    pub fn vbus_removed(&self) -> bool {
+        self.contains(Self::VBUS_REMOVED)
+    }
+
+    pub fn vbus_below_hold(&self) -> bool {
+        self.contains(Self::VBUS_BELOW_HOLD)
+    }
+
+    pub fn battery_connected(&self) -> bool {
+        self.contains(Self::BATTERY_CONNECTED)
+    }
+
+    pub fn battery_removed(&self) -> bool {
+        self.contains(Self::BATTERY_REMOVED)
+    }
+
+    pub fn battery_activation_entered(&self) -> bool {
+        self.contains(Self::BATTERY_ACTIVATION_ENTERED)
+    }
+
+    pub fn battery_activation_exited(&self) -> bool {
+        self.contains(Self::BATTERY_ACTIVATION_EXITED)
+    }
+
+    pub fn charging_started(&self) -> bool {
+        self.contains(Self::CHARGING_STARTED)
+    }
+
+    pub fn charging_finished(&self) -> bool {
+        self.contains(Self::CHARGING_FINISHED)
+    }
+
+    pub fn battery_overtemperature(&self) -> bool {
+        self.contains(Self::BATTERY_OVERTEMPERATURE)
+    }
+
+    pub fn battery_undertemperature(&self) -> bool {
+        self.contains(Self::BATTERY_UNDERTEMPERATURE)
+    }
+
+    pub fn overtemperature(&self) -> bool {
+        self.contains(Self::OVERTEMPERATURE)
+    }
+
+    pub fn charge_current_low(&self) -> bool {
+        self.contains(Self::CHARGE_CURRENT_LOW)
+    }
+
+    pub fn dcdc1_undervoltage(&self) -> bool {
+        self.contains(Self::DCDC1_UNDERVOLTAGE)
+    }
+
+    pub fn dcdc2_undervoltage(&self) -> bool {
+        self.contains(Self::DCDC2_UNDERVOLTAGE)
+    }
+
+    pub fn dcdc3_undervoltage(&self) -> bool {
+        self.contains(Self::DCDC3_UNDERVOLTAGE)
+    }
+
+    pub fn pek_short_press(&self) -> bool {
+        self.contains(Self::PEK_SHORT_PRESS)
+    }
+
+    pub fn pek_long_press(&self) -> bool {
+        self.contains(Self::PEK_LONG_PRESS)
+    }
+
+    pub fn noe_power_on(&self) -> bool {
+        self.contains(Self::NOE_POWER_ON)
+    }
+
+    pub fn noe_power_off(&self) -> bool {
+        self.contains(Self::NOE_POWER_OFF)
+    }
+
+    pub fn vbus_valid(&self) -> bool {
+        self.contains(Self::VBUS_VALID)
+    }
+
+    pub fn vbus_invalid(&self) -> bool {
+        self.contains(Self::VBUS_INVALID)
+    }
+
+    pub fn vbus_session_valid(&self) -> bool {
+        self.contains(Self::VBUS_SESSION_VALID)
+    }
+
+    pub fn vbus_session_end(&self) -> bool {
+        self.contains(Self::VBUS_SESSION_END)
+    }
+
+    pub fn aps_low_level1(&self) -> bool {
+        self.contains(Self::APS_LOW_LEVEL1)
+    }
+
+    pub fn aps_low_level2(&self) -> bool {
+        self.contains(Self::APS_LOW_LEVEL2)
+    }
+
+    pub fn timer_expired(&self) -> bool {
+        self.contains(Self::TIMER_EXPIRED)
+    }
+
+    pub fn pek_rising_edge(&self) -> bool {
+        self.contains(Self::PEK_RISING_EDGE)
+    }
+
+    pub fn pek_falling_edge(&self) -> bool {
+        self.contains(Self::PEK_FALLING_EDGE)
+    }
+
+    pub fn gpio3_edge(&self) -> bool {
+        self.contains(Self::GPIO3_EDGE)
+    }
+
+    pub fn gpio2_edge(&self) -> bool {
+        self.contains(Self::GPIO2_EDGE)
+    }
+
+    pub fn gpio1_edge(&self) -> bool {
+        self.contains(Self::GPIO1_EDGE)
+    }
+
+    pub fn gpio0_edge(&self) -> bool {
+        self.contains(Self::GPIO0_EDGE)
+    }
+}
+
+/// The IRQ enable registers share this layout, so the same flags name
+/// events whether they're being enabled, checked or acknowledged. `Irq` is
+/// just a shorter name for them.
+pub type Irq = IrqStatus;
+
+/// What most likely powered the system on, going by which events are still
+/// latched in the IRQ status registers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerOnSource {
+    /// The power key was pressed
+    PowerKey,
+    /// Power was plugged into ACIN
+    Acin,
+    /// Power was plugged into VBUS
+    Vbus,
+    /// None of the above are latched, so either something already
+    /// acknowledged them or the system came up some other way
+    Unknown,
+}
+
+impl PowerOnSource {
+    /// A key press wins over plugged in power, as power can be connected
+    /// well before the key is used to turn the system on.
+    pub fn from_irq_status(status: IrqStatus) -> Self {
+        let key = IrqStatus::PEK_SHORT_PRESS | IrqStatus::PEK_LONG_PRESS | IrqStatus::PEK_FALLING_EDGE;
+
+        if status.intersects(key) {
+            PowerOnSource::PowerKey
+        } else if status.acin_connected() {
+            PowerOnSource::Acin
+        } else if status.vbus_connected() {
+            PowerOnSource::Vbus
+        } else {
+            PowerOnSource::Unknown
+        }
+    }
+}
+
+/// The APS low voltage warnings, set up with
+/// `Axp209::set_aps_warning_level1_mv()` and its level 2 sibling. Level 1
+/// is meant as the early warning and level 2 as the last call before the
+/// chip cuts power at the VOFF voltage.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LowBatteryEvent {
+    pub warning_level1: bool,
+    pub warning_level2: bool,
+}
+
+impl LowBatteryEvent {
+    pub fn from_irq_status(status: IrqStatus) -> Self {
+        LowBatteryEvent {
+            warning_level1: status.aps_low_level1(),
+            warning_level2: status.aps_low_level2(),
+        }
+    }
+
+    /// Whether either warning went off
+    pub fn any(&self) -> bool {
+        self.warning_level1 || self.warning_level2
+    }
+}
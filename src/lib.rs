@@ -30,6 +30,32 @@
 //!   * Is there a battery attached?
 //! * Using the internal 127 minute timer (see `timer_control`)
 //! * Turning various output voltages on and off
+//!
+//! There's also an async version of the driver in `asynch`, behind the
+//! `async` feature, `nb::Result` versions of the ADC readers behind the
+//! `nb` feature and fixed point ones behind the `fixed` feature.
+//!
+//! Any `embedded_hal::i2c::I2c` bus will do:
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # let i2c = Mock::new(&[Transaction::write_read(0x34, vec![0xb9], vec![0x80 | 87])]);
+//! use axp209::Axp209;
+//!
+//! let mut pmic = Axp209::new(i2c);
+//! println!("Battery level: {}%", pmic.battery_level().unwrap());
+//! # pmic.free().done();
+//! ```
+//!
+//! The driver owns its bus, so when the AXP209 shares it with other chips
+//! hand it one of the `embedded-hal-bus` wrappers instead:
+//!
+//! ```ignore
+//!     let bus = RefCell::new(i2c);
+//!
+//!     let mut pmic = Axp209::new(RefCellDevice::new(&bus));
+//!     let mut rtc = Rtc::new(RefCellDevice::new(&bus));
+//! ```
 //! 
 //! If there's a feature you'd like to see implemented, either
 //! [open an issue](https://github.com/RandomInsano/axp209-rs/issues)
@@ -42,42 +68,160 @@
 //! re-compile my kernel with every AXP20X feature disabled.
 
 #![no_std]
-#![feature(rustc_private)]
 #![deny(warnings)]
 
+#[cfg(test)]
+#[macro_use]
+extern crate std;
+
 extern crate embedded_hal as hal;
 #[macro_use]
 extern crate bitflags;
-extern crate byteorder;
+#[cfg(feature = "async")]
+extern crate embedded_hal_async;
 
 pub mod adc_control;
 pub mod power_status;
 pub mod power_control;
 pub mod charging_status;
 pub mod timer_control;
-
-pub use self::adc_control::AdcControl;
-pub use self::power_status::PowerStatus;
-pub use self::power_control::PowerControl;
+pub mod irq_status;
+pub mod irq_enable;
+pub mod charge_control;
+pub mod vbus_control;
+pub mod measurements;
+pub mod pek_config;
+pub mod shutdown_control;
+pub mod backup_battery;
+pub mod gpio;
+pub mod config;
+pub mod units;
+pub mod summary;
+pub mod vbus_session;
+pub mod calibration;
+pub mod voltage_ramp;
+pub mod monitor;
+pub mod coulomb_control;
+#[cfg(feature = "async")]
+pub mod asynch;
+#[cfg(feature = "nb")]
+pub mod nonblocking;
+#[cfg(feature = "fixed")]
+pub mod fixed_point;
+
+mod conversions;
+
+pub use self::adc_control::{AdcControl, AdcSampleRate};
+pub use self::power_status::{PowerStatus, PowerMode};
+pub use self::power_control::{PowerControl, OutputControl, Rail};
 pub use self::charging_status::ChargingStatus;
 pub use self::timer_control::TimerControl;
-
-use byteorder::{ByteOrder, BigEndian};
-use hal::blocking::i2c::{Read, Write, WriteRead};
+pub use self::irq_status::{Irq, IrqStatus, PowerOnSource, LowBatteryEvent};
+pub use self::irq_enable::IrqEnable;
+pub use self::charge_control::{ChargeTargetVoltage, ChargeTimeouts, ChargeTimeout, PrechargeTimeout};
+pub use self::vbus_control::VbusCurrentLimit;
+pub use self::measurements::{Measurements, AdcBlock};
+pub use self::pek_config::{PekConfig, PekPowerOnTime, PekLongPressTime, PekPowerOffTime, PwrokDelay};
+pub use self::shutdown_control::{ChgLedMode, NoeDelay};
+pub use self::backup_battery::{BackupBatteryConfig, BackupBatteryVoltage, BackupBatteryCurrent};
+pub use self::gpio::{Gpio, GpioMode, GpioAdcRange};
+pub use self::config::{Config, CoulombConfig};
+pub use self::units::{Millivolts, Milliamps, Celsius};
+pub use self::summary::PmicSummary;
+pub use self::vbus_session::VbusSessionStatus;
+pub use self::calibration::{Calibration, ChannelCalibration};
+pub use self::voltage_ramp::{Dcdc2Vrc, VrcSlope};
+pub use self::monitor::Monitor;
+pub use self::coulomb_control::CoulombControl;
+
+use hal::i2c::I2c;
 
 pub const BATTERY_LEVEL_MISSING: u8 = 0x7f;
-/// The address can't be changed
-const ADDRESS: u8 = 0x34;
 
-enum Registers {
+/// Size of one ADC step, for anyone applying their own calibration to the
+/// raw readings. Voltages are in microvolts and currents in microamps.
+pub const BATTERY_VOLTAGE_LSB_UV: u32 = 1100;
+pub const BATTERY_CURRENT_LSB_UA: u32 = 500;
+pub const ACIN_VOLTAGE_LSB_UV: u32 = 1700;
+pub const ACIN_CURRENT_LSB_UA: u32 = 625;
+pub const VBUS_VOLTAGE_LSB_UV: u32 = 1700;
+pub const VBUS_CURRENT_LSB_UA: u32 = 375;
+pub const TS_VOLTAGE_LSB_UV: u32 = 800;
+pub const GPIO_VOLTAGE_LSB_UV: u32 = 500;
+pub const IPSOUT_VOLTAGE_LSB_UV: u32 = 1400;
+/// In thousandths of a degree celcius
+pub const TEMPERATURE_LSB_MC: u32 = 100;
+/// The only address the AXP209 answers on, it can't be changed
+pub const AXP209_DEFAULT_ADDRESS: u8 = 0x34;
+const ADDRESS: u8 = AXP209_DEFAULT_ADDRESS;
+
+/// Everything that can go wrong talking to the chip
+#[derive(Debug, PartialEq)]
+pub enum Error<E> {
+    /// The I2C bus had a problem
+    I2c(E),
+    /// The chip answered, but with a value that doesn't make sense
+    InvalidData,
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(error: E) -> Self {
+        Error::I2c(error)
+    }
+}
+
+/// Addresses of the registers the driver knows about, for use with
+/// `read_register()` and friends
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Registers {
     /// Power status and control registers
     PowerStatus = 0x00,
     ChargingStatus = 0x01,
+    VbusSessionStatus = 0x02,
+    /// The low nibble identifies the chip, see `detect_variant()`
+    ChipVersion = 0x03,
     PowerControl = 0x12,
+    /// Six bytes of scratch space (0x06 - 0x0b) kept alive by the backup battery
+    DataBuffer = 0x06,
+    VbusControl = 0x30,
+    PowerOffVoltage = 0x31,
+    /// Shutdown, battery detection and CHGLED control
+    ShutdownControl = 0x32,
+    ChargeControl = 0x33,
+    ChargeControl2 = 0x34,
+    BackupBatteryControl = 0x35,
+    PekConfig = 0x36,
+    DcdcFrequency = 0x37,
+    /// TS pin thresholds for battery temperature protection. A thermistor's
+    /// voltage drops as it heats up, so the low temperature thresholds are
+    /// the higher voltages.
+    ChargeTempLow = 0x38,
+    ChargeTempHigh = 0x39,
+    /// APS voltages raising the low power warning IRQs
+    ApsWarningLevel1 = 0x3a,
+    ApsWarningLevel2 = 0x3b,
+    DischargeTempLow = 0x3c,
+    DischargeTempHigh = 0x3d,
     TimerControl = 0x8a,
+    /// Over-temperature shutdown and a few other odds and ends
+    OverTemperature = 0x8f,
+    /// First of the five IRQ enable registers
+    IrqEnable = 0x40,
+    /// First of the five IRQ status registers
+    IrqStatus = 0x48,
+
+    /// Output voltage settings
+    Dcdc2Voltage = 0x23,
+    /// DCDC2 and LDO3 voltage ramp control
+    VoltageRamp = 0x25,
+    Dcdc3Voltage = 0x27,
+    Ldo24Voltage = 0x28,
+    Ldo3Voltage = 0x29,
 
     /// ADC Control
     AdcControl = 0x82,    
+    AdcSpeed = 0x84,
+    GpioAdcRange = 0x85,
 
     /// ADC Value registers
     AcinVoltage = 0x56,
@@ -88,33 +232,209 @@ enum Registers {
     BatteryTemperature = 0x62,
     Gpio0Voltage = 0x64,
     Gpio1Voltage = 0x66,
-    //InstantaniousBatteryPower = 0x70, // Three bytes?!
+    InstantaneousBatteryPower = 0x70, // Three bytes?!
     BatteryVoltage = 0x78,
     BatteryChargeCurrent = 0x7a,
     BatteryDischargeCurrent = 0x7c,
     SystemIpsout = 0x7e,
 
-    //CoulombBattery = 0xb0,
-    //CoulombBatteryDischarge = 0xb4,
-    //CoulombBatteryEncryption = 0xb8,
+    /// GPIO control
+    Gpio0Control = 0x90,
+    Gpio1Control = 0x92,
+    Gpio2Control = 0x93,
+    GpioState = 0x94,
+    Gpio3Control = 0x95,
+
+    CoulombBattery = 0xb0,
+    CoulombBatteryDischarge = 0xb4,
+    CoulombBatteryEncryption = 0xb8,
     BatteryLevel = 0xb9,
 }
 
+/// For the `_checked` setters, which compare what was written with what
+/// the chip reports back
+fn check_readback<E>(expected: u16, actual: u16) -> Result<(), Error<E>> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::InvalidData)
+    }
+}
+
+/// Which chip `detect_variant()` found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChipVariant {
+    /// The AXP209 or the AXP202. Both report 0x1 in the low nibble of
+    /// register 0x03, and as far as the datasheets go they share their
+    /// register map and ADC scaling, so there's nothing left to tell them
+    /// apart by.
+    Axp20x,
+    /// Some other chip version, with the nibble that was read
+    Unknown(u8),
+}
+
+impl ChipVariant {
+    /// Decodes the low nibble of register 0x03
+    pub fn from_register(value: u8) -> Self {
+        match value & 0x0f {
+            0x1 => ChipVariant::Axp20x,
+            other => ChipVariant::Unknown(other),
+        }
+    }
+}
+
+impl From<Registers> for u8 {
+    fn from(register: Registers) -> u8 {
+        register as u8
+    }
+}
+
 pub struct Axp209<I2C> {
     device: I2C,
+    coulomb: Option<CoulombConfig>,
+    calibration: Calibration,
 }
 
 impl<I2C, E> Axp209<I2C>
 where
-    I2C: WriteRead<Error = E> + Write<Error = E> + Read<Error = E>,
+    I2C: I2c<Error = E>,
 {
     pub fn new(dev: I2C) -> Self {
         Axp209 {
             device: dev,
+            coulomb: None,
+            calibration: Calibration::default(),
+        }
+    }
+
+    /// Like `new()`, correcting the ADC readings with `calibration`
+    pub fn with_calibration(dev: I2C, calibration: Calibration) -> Self {
+        let mut pmic = Self::new(dev);
+        pmic.calibration = calibration;
+        pmic
+    }
+
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+
+    /// Same as `new()`, spelling out that the chip is always found at
+    /// `AXP209_DEFAULT_ADDRESS`
+    pub fn new_default(dev: I2C) -> Self {
+        Self::new(dev)
+    }
+
+    /// Like `new()`, but first checks something acknowledges at
+    /// `AXP209_DEFAULT_ADDRESS` by reading the power status. The bus error
+    /// (usually a NACK) is handed back if nothing does. For a more thorough
+    /// check that it's really an AXP209, see `probe()`.
+    pub fn try_new(dev: I2C) -> Result<Self, Error<E>> {
+        let mut pmic = Self::new(dev);
+        pmic.power_status()?;
+
+        Ok(pmic)
+    }
+
+    /// Reads the chip version. The conversions don't depend on it, since
+    /// the only chips reporting this family's version all scale their
+    /// readings the same way.
+    pub fn detect_variant(&mut self) -> Result<ChipVariant, Error<E>> {
+        let value = self.get_8bit_register(Registers::ChipVersion as u8)?;
+
+        Ok(ChipVariant::from_register(value))
+    }
+
+    /// Hands the I2C bus back once you're done with the chip
+    pub fn free(self) -> I2C {
+        self.device
+    }
+
+    /// Applies every setting in `config`, stopping at the first error. See
+    /// the `config` module for the order they're written in.
+    pub fn configure(&mut self, config: &Config) -> Result<(), Error<E>> {
+        if let Some(voltage) = config.charge_target_voltage {
+            self.set_charge_target_voltage(voltage)?;
+        }
+        if let Some(ma) = config.charge_current_ma {
+            self.set_charge_current_ma(ma)?;
+        }
+        if let Some(mv) = config.dcdc2_voltage_mv {
+            self.set_dcdc2_voltage_mv(mv)?;
+        }
+        if let Some(mv) = config.dcdc3_voltage_mv {
+            self.set_dcdc3_voltage_mv(mv)?;
+        }
+        if let Some(mv) = config.ldo2_voltage_mv {
+            self.set_ldo2_voltage_mv(mv)?;
+        }
+        if let Some(mv) = config.ldo3_voltage_mv {
+            self.set_ldo3_voltage_mv(mv)?;
+        }
+        if let Some(value) = config.adc_control {
+            self.set_adc_control(value)?;
+        }
+
+        Ok(())
+    }
+
+    /// The six bytes of the data buffer. They survive a reset, and a power
+    /// cycle too as long as the backup battery holds up.
+    pub fn read_data_buffer(&mut self) -> Result<[u8; 6], Error<E>> {
+        self.read_registers(Registers::DataBuffer as u8)
+    }
+
+    pub fn write_data_buffer(&mut self, data: &[u8; 6]) -> Result<(), Error<E>> {
+        self.set_8bit_registers(Registers::DataBuffer as u8, data)
+    }
+
+    /// A single byte of the data buffer. Indexes past 5 are reported as
+    /// `Error::InvalidData`.
+    pub fn data_buffer_byte(&mut self, index: usize) -> Result<u8, Error<E>> {
+        if index >= 6 {
+            return Err(Error::InvalidData);
+        }
+
+        self.get_8bit_register(Registers::DataBuffer as u8 + index as u8)
+    }
+
+    pub fn set_data_buffer_byte(&mut self, index: usize, value: u8) -> Result<(), Error<E>> {
+        if index >= 6 {
+            return Err(Error::InvalidData);
         }
+
+        self.set_8bit_register(Registers::DataBuffer as u8 + index as u8, value)
+    }
+
+    /// Puts the datasheet's reset values back into, in this order:
+    /// * Charge control 1 (0x33): charging on, 4.2V, 1100mA
+    /// * ADC enable (0x82, 0x83): battery voltage, battery current and
+    ///   TS pin, plus the internal temperature
+    /// * IRQ enable (0x40 - 0x44)
+    ///
+    /// The output control register (0x12) is deliberately left alone. Its
+    /// reset value depends on how the board straps the power-on sequence,
+    /// and guessing wrong would cut power to whatever we're running on.
+    pub fn reset_defaults(&mut self) -> Result<(), Error<E>> {
+        self.set_8bit_register(Registers::ChargeControl as u8, 0xc8)?;
+        self.set_8bit_registers(Registers::AdcControl as u8, &[0x83, 0x80])?;
+        self.set_8bit_registers(Registers::IrqEnable as u8, &[0xd8, 0xff, 0x3b, 0xc1, 0x00])
+    }
+
+    /// Checks there's really an AXP209 on the other end of the bus by writing
+    /// a pattern into the data buffer and reading it back. Whatever was in
+    /// the buffer beforehand is put back afterwards.
+    pub fn probe(&mut self) -> Result<bool, Error<E>> {
+        const PATTERN: [u8; 6] = [ 0x55, 0xaa, 0x0f, 0xf0, 0x3c, 0xc3 ];
+
+        let original = self.read_data_buffer()?;
+        self.write_data_buffer(&PATTERN)?;
+        let readback = self.read_data_buffer()?;
+        self.write_data_buffer(&original)?;
+
+        Ok(readback == PATTERN)
     }
 
-    fn write_read_byte(&mut self, send: u8) -> Result<u8, E> {
+    fn write_read_byte(&mut self, send: u8) -> Result<u8, Error<E>> {
         let comm: [u8; 1] = [ send ];
         let mut buf: [u8; 1] = [0];
         self.device.write_read(ADDRESS, &comm, &mut buf)?;
@@ -124,21 +444,15 @@ where
 
     /// Many ADC functions on this chip provide their values as a strange
     /// 10bit value that requires some funky shifting
-    fn get_adc_12bits(&mut self, register: u8) -> Result<u16, E> {
-        let comm: [u8; 1] = [ register ];
+    fn get_adc_12bits(&mut self, register: u8) -> Result<u16, Error<E>> {
         let mut recv: [u8; 2] = [ 0, 0 ];
-        let mut value: u16;
-
-        self.device.write_read(ADDRESS, &comm, &mut recv)?;
 
-        // Weird way to store a number if ye ask me!
-        value = (recv[0] as u16) << 4;
-        value |= recv[1] as u16 & 0x0f;
+        self.get_8bit_registers(register, &mut recv)?;
 
-        Ok(value)
+        Ok(conversions::adc_12bits(&recv))
     }
 
-    fn get_8bit_register(&mut self, register: u8) -> Result<u8, E> {
+    fn get_8bit_register(&mut self, register: u8) -> Result<u8, Error<E>> {
         let comm: [u8; 1] = [ register ];
         let mut buf: [u8; 1] = [0];
 
@@ -147,7 +461,16 @@ where
         Ok(buf[0])
     }
 
-    fn set_8bit_register(&mut self, register: u8, value: u8) -> Result<(), E> {
+    /// Reads consecutive registers in a single transaction
+    fn get_8bit_registers(&mut self, register: u8, buf: &mut [u8]) -> Result<(), Error<E>> {
+        let comm: [u8; 1] = [ register ];
+
+        self.device.write_read(ADDRESS, &comm, buf)?;
+
+        Ok(())
+    }
+
+    fn set_8bit_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
         let comm: [u8; 2] = [ register, value ];
 
         self.device.write(ADDRESS, &comm)?;
@@ -155,210 +478,3199 @@ where
         Ok(())
     }
 
-    fn get_16bit_register(&mut self, register: u8) -> Result<u16, E> {
+    /// The chip only auto-increments the register address when reading, so
+    /// consecutive registers have to be written one at a time.
+    fn set_8bit_registers(&mut self, register: u8, values: &[u8]) -> Result<(), Error<E>> {
+        for (i, value) in values.iter().enumerate() {
+            self.set_8bit_register(register + i as u8, *value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Escape hatch for registers the driver doesn't know about (yet)
+    pub fn read_register(&mut self, register: u8) -> Result<u8, Error<E>> {
+        self.write_read_byte(register)
+    }
+
+    pub fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.set_8bit_register(register, value)
+    }
+
+    /// Reads `N` consecutive registers starting at `start` in a single
+    /// transaction
+    pub fn read_registers<const N: usize>(&mut self, start: u8) -> Result<[u8; N], Error<E>> {
+        let mut buf: [u8; N] = [0; N];
+
+        self.get_8bit_registers(start, &mut buf)?;
+
+        Ok(buf)
+    }
+
+    /// Reads a register, hands the value to `f` and writes back whatever it
+    /// returns. Good for changing a few bits while leaving the rest alone.
+    pub fn modify_register(&mut self, register: u8, f: impl FnOnce(u8) -> u8) -> Result<(), Error<E>> {
+        let value = self.get_8bit_register(register)?;
+
+        self.set_8bit_register(register, f(value))
+    }
+
+    fn get_16bit_register(&mut self, register: u8) -> Result<u16, Error<E>> {
         let comm: [u8; 1] = [ register ];
         let mut buf: [u8; 2] = [0, 0];
 
         self.device.write_read(ADDRESS, &comm, &mut buf)?;
 
-        Ok(BigEndian::read_u16(&buf))
+        Ok(u16::from_be_bytes(buf))
+    }
+
+    fn get_32bit_register(&mut self, register: u8) -> Result<u32, Error<E>> {
+        Ok(u32::from_be_bytes(self.read_registers(register)?))
     }
 
-    pub fn adc_control(&mut self) -> Result<AdcControl, E> {
+    pub fn adc_control(&mut self) -> Result<AdcControl, Error<E>> {
         Ok(AdcControl::new(self.get_16bit_register(Registers::AdcControl as u8)?))
     }
 
-    pub fn power_status(&mut self) -> Result<PowerStatus, E> {
+    /// Whether every channel in `channels` is being sampled, for working out
+    /// why a reading is stuck at zero. `adc_control()` has a predicate for
+    /// each channel too.
+    pub fn adc_enabled(&mut self, channels: AdcControl) -> Result<bool, Error<E>> {
+        Ok(self.adc_control()?.contains(channels))
+    }
+
+    /// Picks which ADC channels get sampled. Readings from a disabled channel
+    /// are stale (or zero on a fresh chip).
+    pub fn set_adc_control(&mut self, value: AdcControl) -> Result<(), Error<E>> {
+        self.set_8bit_registers(Registers::AdcControl as u8, &value.bits().to_be_bytes())
+    }
+
+    /// Turns on every ADC channel
+    pub fn enable_all_adc(&mut self) -> Result<(), Error<E>> {
+        self.set_adc_control(AdcControl::all())
+    }
+
+    /// The TS pin settings sharing the register are left alone
+    pub fn set_adc_sample_rate(&mut self, rate: AdcSampleRate) -> Result<(), Error<E>> {
+        self.modify_register(Registers::AdcSpeed as u8, |value| (value & 0x3f) | rate.to_register())
+    }
+
+    pub fn adc_sample_rate(&mut self) -> Result<AdcSampleRate, Error<E>> {
+        let value = self.get_8bit_register(Registers::AdcSpeed as u8)?;
+
+        Ok(AdcSampleRate::from_register(value))
+    }
+
+    pub fn power_status(&mut self) -> Result<PowerStatus, Error<E>> {
         Ok(PowerStatus::new(self.get_8bit_register(Registers::PowerStatus as u8)?))
     }
 
-    pub fn power_control(&mut self) -> Result<PowerControl, E> {
+    pub fn vbus_session_status(&mut self) -> Result<VbusSessionStatus, Error<E>> {
+        Ok(VbusSessionStatus::new(self.get_8bit_register(Registers::VbusSessionStatus as u8)?))
+    }
+
+    pub fn power_control(&mut self) -> Result<PowerControl, Error<E>> {
         Ok(PowerControl::new(self.get_8bit_register(Registers::PowerControl as u8)?))
     }
 
-    pub fn set_power_control(&mut self, value: PowerControl) -> Result<(), E> {
-        Ok(self.set_8bit_register(Registers::PowerControl as u8, value.bits())?)
+    pub fn set_power_control(&mut self, value: PowerControl) -> Result<(), Error<E>> {
+        self.set_8bit_register(Registers::PowerControl as u8, value.bits())
     }
 
-    pub fn charging_status(&mut self) -> Result<ChargingStatus, E> {
-        Ok(ChargingStatus::new(self.get_8bit_register(Registers::ChargingStatus as u8)?))
+    /// Turn a single output on or off. The Power Control register is read
+    /// first so the other rails (and the reserved bits) are left as they were.
+    pub fn set_rail_enabled(&mut self, rail: Rail, on: bool) -> Result<(), Error<E>> {
+        let mut value = self.power_control()?;
+        value.set(rail.flag(), on);
+
+        self.set_power_control(value)
     }
 
-    pub fn timer_control(&mut self) -> Result<TimerControl, E> {
-        Ok(TimerControl::new(self.get_8bit_register(Registers::TimerControl as u8)?))
+    pub fn rail_enabled(&mut self, rail: Rail) -> Result<bool, Error<E>> {
+        Ok(self.power_control()?.contains(rail.flag()))
     }
 
-    pub fn set_timer_control(&mut self, value: TimerControl) -> Result<(), E> {
-        Ok(self.set_8bit_register(Registers::TimerControl as u8, value.bits())?)
+    /// Sets every rail at once: the ones in `value` on, the rest off. Unlike
+    /// `set_power_control()` the reserved bits are left as they were.
+    pub fn set_output_control(&mut self, value: OutputControl) -> Result<(), Error<E>> {
+        let mask = OutputControl::all().bits();
+
+        self.modify_register(Registers::PowerControl as u8, |current| (current & !mask) | (value.bits() & mask))
     }
 
-    /// In milliamps
-    pub fn battery_discharging_current(&mut self) -> Result<u16, E> {
-        let comm: [u8; 1] = [ Registers::BatteryDischargeCurrent as u8 ];
-        let mut recv: [u8; 2] = [ 0, 0 ];
-        let mut value: u16;
+    /// The rails that are switched on, without the reserved bits
+    pub fn output_control(&mut self) -> Result<OutputControl, Error<E>> {
+        Ok(self.power_control()? & OutputControl::all())
+    }
+
+    /// In millivolts. Anything outside of 700mV - 2275mV is clamped and the
+    /// rest is rounded to the nearest 25mV step, so 712mV becomes 700mV while
+    /// 713mV becomes 725mV, and 2280mV ends up at 2275mV. The upper two bits
+    /// of the register are reserved and left untouched.
+    pub fn set_dcdc2_voltage_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let steps = conversions::dcdc2_to_register(mv);
+        self.modify_register(Registers::Dcdc2Voltage as u8, |value| (value & 0xc0) | steps)
+    }
+
+    /// In millivolts
+    pub fn dcdc2_voltage_mv(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::Dcdc2Voltage as u8)?;
+
+        Ok(conversions::dcdc2_voltage_mv(value))
+    }
+
+    /// How DCDC2 ramps between voltages. LDO3's bits in the same register
+    /// are preserved.
+    pub fn set_dcdc2_vrc(&mut self, cfg: Dcdc2Vrc) -> Result<(), Error<E>> {
+        self.modify_register(Registers::VoltageRamp as u8, |value| (value & !Dcdc2Vrc::MASK) | cfg.to_register())
+    }
 
-        self.device.write_read(ADDRESS, &comm, &mut recv)?;
+    pub fn dcdc2_vrc(&mut self) -> Result<Dcdc2Vrc, Error<E>> {
+        let value = self.get_8bit_register(Registers::VoltageRamp as u8)?;
 
-        // Of course one would have 5 least significant bits and
-        // ruin my get_adc_12bits function above!
-        value = (recv[0] as u16) << 5;
-        value |= recv[1] as u16 & 0x1f;
+        Ok(Dcdc2Vrc::from_register(value))
+    }
+
+    /// Walks DCDC2 from its current voltage to `target_mv`, `step_mv` at a
+    /// time, so a CPU rail doesn't jump in one go. The target is clamped and
+    /// rounded like `set_dcdc2_voltage_mv()`, and a step of 0 gives
+    /// `Error::InvalidData`. The register is only read once, so nothing else
+    /// should be touching DCDC2 meanwhile. Pauses between the steps are up
+    /// to the chip's own ramp control, see `set_dcdc2_vrc()`.
+    pub fn ramp_dcdc2_voltage_mv(&mut self, target_mv: u16, step_mv: u16) -> Result<(), Error<E>> {
+        if step_mv == 0 {
+            return Err(Error::InvalidData);
+        }
+
+        let value = self.get_8bit_register(Registers::Dcdc2Voltage as u8)?;
+        let target = conversions::dcdc2_voltage_mv(conversions::dcdc2_to_register(target_mv));
+        let mut mv = conversions::dcdc2_voltage_mv(value);
+
+        while mv != target {
+            mv = if mv < target {
+                core::cmp::min(mv.saturating_add(step_mv), target)
+            } else {
+                core::cmp::max(mv.saturating_sub(step_mv), target)
+            };
+
+            self.set_8bit_register(Registers::Dcdc2Voltage as u8, (value & 0xc0) | conversions::dcdc2_to_register(mv))?;
+        }
+
+        Ok(())
+    }
 
-        Ok(value / 2)
-    }    
+    /// In millivolts. Same deal as DCDC2 except the range goes all the way
+    /// up to 3500mV, using the lower seven bits of the register.
+    pub fn set_dcdc3_voltage_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let steps = conversions::dcdc3_to_register(mv);
+        self.modify_register(Registers::Dcdc3Voltage as u8, |value| (value & 0x80) | steps)
+    }
 
     /// In millivolts
-    pub fn battery_voltage(&mut self) -> Result<u16, E> {
-        let mut value = self.get_adc_12bits(Registers::BatteryVoltage as u8)?;
+    pub fn dcdc3_voltage_mv(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::Dcdc3Voltage as u8)?;
 
-        // Voltage is in 1.1mV increments, so just add 1/10 the value and
-        // avoid those pesky floating point multiplications. :D
-        value += value / 10;
+        Ok(conversions::dcdc3_voltage_mv(value))
+    }
 
-        Ok(value)
+    /// In millivolts, between 1800mV and 3300mV in 100mV steps. LDO2 only
+    /// gets the high nibble of its register, the low one belongs to LDO4.
+    pub fn set_ldo2_voltage_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let steps = conversions::ldo2_to_register(mv);
+        self.modify_register(Registers::Ldo24Voltage as u8, |value| (value & 0x0f) | steps)
     }
 
-    /// In milliamps
-    pub fn battery_charging_current(&mut self) -> Result<u16, E> {
-        let value = self.get_adc_12bits(Registers::BatteryChargeCurrent as u8)?;
+    /// In millivolts
+    pub fn ldo2_voltage_mv(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::Ldo24Voltage as u8)?;
+
+        Ok(conversions::ldo2_voltage_mv(value))
+    }
 
-        Ok(value / 2)
+    /// In millivolts. Despite what you'd guess, LDO3 doesn't share a register
+    /// with LDO2 (that's LDO4) and instead has its own with the same 700mV -
+    /// 3500mV range in 25mV steps as DCDC3. The top bit picks between LDO
+    /// and tracking mode and is left alone.
+    pub fn set_ldo3_voltage_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let steps = conversions::ldo3_to_register(mv);
+        self.modify_register(Registers::Ldo3Voltage as u8, |value| (value & 0x80) | steps)
     }
 
     /// In millivolts
-    pub fn acin_voltage(&mut self) -> Result<u16, E> {
-        let mut value = self.get_adc_12bits(Registers::AcinVoltage as u8)?;
+    pub fn ldo3_voltage_mv(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::Ldo3Voltage as u8)?;
 
-        value += value / 7;
+        Ok(conversions::ldo3_voltage_mv(value))
+    }
+
+    /// `set_dcdc2_voltage_mv()`, then reads the voltage back and gives
+    /// `Error::InvalidData` if it isn't the step that was written. Values
+    /// are still clamped and rounded first, that isn't counted as a mismatch.
+    pub fn set_dcdc2_voltage_mv_checked(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_dcdc2_voltage_mv(mv)?;
 
-        Ok(value)
+        let expected = conversions::dcdc2_voltage_mv(conversions::dcdc2_to_register(mv));
+        check_readback(expected, self.dcdc2_voltage_mv()?)
     }
 
-    /// In milliamps
-    pub fn acin_current(&mut self) -> Result<u16, E> {
-        let mut value = self.get_adc_12bits(Registers::AcinCurrent as u8)?;
+    /// Same as `set_dcdc2_voltage_mv_checked()`, for DCDC3
+    pub fn set_dcdc3_voltage_mv_checked(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_dcdc3_voltage_mv(mv)?;
+
+        let expected = conversions::dcdc3_voltage_mv(conversions::dcdc3_to_register(mv));
+        check_readback(expected, self.dcdc3_voltage_mv()?)
+    }
 
-        // Trying to avoid too much rounding as it's multiples of 0.625 milliamps.
-        // For similar odd math with explination, check out vbus_current()
-        value = ((value * 16) / 10) / 16;
+    /// Same as `set_dcdc2_voltage_mv_checked()`, for LDO2
+    pub fn set_ldo2_voltage_mv_checked(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_ldo2_voltage_mv(mv)?;
 
-        Ok(value)
+        let expected = conversions::ldo2_voltage_mv(conversions::ldo2_to_register(mv));
+        check_readback(expected, self.ldo2_voltage_mv()?)
     }
 
-    /// In milliamps
-    pub fn vbus_voltage(&mut self) -> Result<u16, E> {
-        let mut value = self.get_adc_12bits(Registers::VbusVoltage as u8)?;
+    /// Same as `set_dcdc2_voltage_mv_checked()`, for LDO3
+    pub fn set_ldo3_voltage_mv_checked(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_ldo3_voltage_mv(mv)?;
+
+        let expected = conversions::ldo3_voltage_mv(conversions::ldo3_to_register(mv));
+        check_readback(expected, self.ldo3_voltage_mv()?)
+    }
+
+    pub fn charging_status(&mut self) -> Result<ChargingStatus, Error<E>> {
+        Ok(ChargingStatus::new(self.get_8bit_register(Registers::ChargingStatus as u8)?))
+    }
+
+    /// Same as `charging_status()?.charging()`
+    pub fn is_charging(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.charging_status()?.charging())
+    }
+
+    /// Whether the battery is what's keeping the system up: it's there, it
+    /// isn't charging, current is flowing out of it and neither ACIN nor
+    /// VBUS is usable. With a full battery and external power both this and
+    /// `is_charging()` are false.
+    pub fn is_discharging(&mut self) -> Result<bool, Error<E>> {
+        // Power status and charging status are back to back
+        let recv: [u8; 2] = self.read_registers(Registers::PowerStatus as u8)?;
+
+        let power = PowerStatus::new(recv[0]);
+        let charging = ChargingStatus::new(recv[1]);
+
+        Ok(charging.battery_present()
+            && !charging.charging()
+            && !power.charging()
+            && !power.acin_valid()
+            && !power.vbus_valid())
+    }
+
+    /// See `PowerMode::from_status()` for how it's worked out
+    pub fn power_mode(&mut self) -> Result<PowerMode, Error<E>> {
+        let recv: [u8; 2] = self.read_registers(Registers::PowerStatus as u8)?;
+
+        Ok(PowerMode::from_status(PowerStatus::new(recv[0]), ChargingStatus::new(recv[1])))
+    }
+
+    pub fn timer_control(&mut self) -> Result<TimerControl, Error<E>> {
+        Ok(TimerControl::new(self.get_8bit_register(Registers::TimerControl as u8)?))
+    }
+
+    pub fn set_timer_control(&mut self, value: TimerControl) -> Result<(), Error<E>> {
+        self.set_8bit_register(Registers::TimerControl as u8, value.bits())
+    }
+
+    /// In millivolts. The system gets shut down when the supply drops below
+    /// this, between 2600mV and 3300mV in 100mV steps. Values are clamped and
+    /// the other bits in the register are preserved.
+    pub fn set_voff_voltage_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        let steps = conversions::voff_to_register(mv);
+        self.modify_register(Registers::PowerOffVoltage as u8, |value| (value & !0b111) | steps)
+    }
+
+    /// In millivolts
+    pub fn voff_voltage_mv(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::PowerOffVoltage as u8)?;
+
+        Ok(conversions::voff_voltage_mv(value))
+    }
+
+    /// Whether a power key or GPIO edge wakes the system from sleep. This is
+    /// bit 3 of the VOFF register, the voltage bits are left alone.
+    pub fn set_sleep_mode(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::PowerOffVoltage as u8, |value| {
+            if on { value | 0x08 } else { value & !0x08 }
+        })
+    }
+
+    pub fn sleep_mode(&mut self) -> Result<bool, Error<E>> {
+        let value = self.get_8bit_register(Registers::PowerOffVoltage as u8)?;
+
+        Ok(value & 0x08 != 0)
+    }
+
+    /// Powers off the whole system, right now. There's no confirmation and
+    /// no delay, so make sure everything that needs saving has been saved
+    /// (and filesystems unmounted) before calling this. Only bit 7 of the
+    /// shutdown register is changed, the battery detection and CHGLED
+    /// settings are written back as they were.
+    pub fn shutdown(&mut self) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ShutdownControl as u8, |value| value | 0x80)
+    }
+
+    /// The shutdown path for battery powered devices: `flags` go into the
+    /// data buffer for the next boot to find, charging is disabled, EXTEN,
+    /// LDO3 and LDO4 are switched off, then the system is powered off with
+    /// `shutdown()`. DCDC2, DCDC3 and LDO2 are left up to the end, as
+    /// they're usually the CPU, DRAM and RTC. Charging stays disabled until
+    /// something turns it back on with `set_charging_enabled()`.
+    pub fn safe_power_off(&mut self, flags: &[u8; 6]) -> Result<(), Error<E>> {
+        self.write_data_buffer(flags)?;
+        self.set_charging_enabled(false)?;
+
+        let mut rails = self.power_control()?;
+        rails.remove(PowerControl::EXTEN | PowerControl::LDO3 | PowerControl::LDO4);
+        self.set_power_control(rails)?;
+
+        self.shutdown()
+    }
+
+    /// Only takes effect once the pin is under manual control, see
+    /// `set_chgled_source()`. The rest of the register, the shutdown bit
+    /// included, is written back as it was read.
+    pub fn set_chgled_mode(&mut self, mode: ChgLedMode) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ShutdownControl as u8, |value| {
+            (value & !0x30) | mode.to_register()
+        })
+    }
+
+    pub fn chgled_mode(&mut self) -> Result<ChgLedMode, Error<E>> {
+        let value = self.get_8bit_register(Registers::ShutdownControl as u8)?;
+
+        Ok(ChgLedMode::from_register(value))
+    }
+
+    /// Whether the CHGLED pin follows `set_chgled_mode()` (manual) or is
+    /// driven by the charger to show the charging state
+    pub fn set_chgled_source(&mut self, manual: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ShutdownControl as u8, |value| {
+            if manual { value | 0x08 } else { value & !0x08 }
+        })
+    }
+
+    /// Without battery detection the battery present bit in
+    /// `charging_status()` can't be trusted. Only bit 6 of the shutdown
+    /// register is changed.
+    pub fn set_battery_detection(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ShutdownControl as u8, |value| {
+            if on { value | 0x40 } else { value & !0x40 }
+        })
+    }
+
+    pub fn battery_detection_enabled(&mut self) -> Result<bool, Error<E>> {
+        let value = self.get_8bit_register(Registers::ShutdownControl as u8)?;
 
-        value += value / 7;
+        Ok(value & 0x40 != 0)
+    }
+
+    /// Only the delay bits are changed, so this won't shut anything down
+    pub fn set_noe_shutdown_delay(&mut self, delay: NoeDelay) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ShutdownControl as u8, |value| (value & !0b11) | delay.to_register())
+    }
+
+    pub fn noe_shutdown_delay(&mut self) -> Result<NoeDelay, Error<E>> {
+        let value = self.get_8bit_register(Registers::ShutdownControl as u8)?;
+
+        Ok(NoeDelay::from_register(value))
+    }
+
+    /// Moves the DC-DC switching frequency away from the 1.5MHz default,
+    /// in steps of roughly 8%. The field is four bits with the default in
+    /// the middle, so `steps` is clamped to -8..=7. The reserved upper bits
+    /// are left alone.
+    pub fn set_dcdc_frequency(&mut self, steps: i8) -> Result<(), Error<E>> {
+        let code = (steps.clamp(-8, 7) + 8) as u8;
+        self.modify_register(Registers::DcdcFrequency as u8, |value| (value & 0xf0) | code)
+    }
+
+    /// In steps from the 1.5MHz default, see `set_dcdc_frequency()`
+    pub fn dcdc_frequency(&mut self) -> Result<i8, Error<E>> {
+        let value = self.get_8bit_register(Registers::DcdcFrequency as u8)?;
+
+        Ok((value & 0x0f) as i8 - 8)
+    }
+
+    /// Whether the chip powers everything off once it overheats. That's
+    /// bit 2 of 0x8f, the rest of the register is preserved.
+    pub fn set_overtemperature_shutdown(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::OverTemperature as u8, |value| {
+            if on { value | 0x04 } else { value & !0x04 }
+        })
+    }
+
+    pub fn overtemperature_shutdown(&mut self) -> Result<bool, Error<E>> {
+        let value = self.get_8bit_register(Registers::OverTemperature as u8)?;
+
+        Ok(value & 0x04 != 0)
+    }
 
-        Ok(value)
+    /// There's no separate enable bit for the limit (bit 6 of the register
+    /// is the VHOLD voltage limit), so use `VbusCurrentLimit::Unlimited` to
+    /// turn it off. The rest of the register is preserved.
+    pub fn set_vbus_current_limit(&mut self, limit: VbusCurrentLimit) -> Result<(), Error<E>> {
+        self.modify_register(Registers::VbusControl as u8, |value| (value & !0b11) | limit.to_register())
+    }
+
+    pub fn vbus_current_limit(&mut self) -> Result<VbusCurrentLimit, Error<E>> {
+        let value = self.get_8bit_register(Registers::VbusControl as u8)?;
+
+        Ok(VbusCurrentLimit::from_register(value))
+    }
+
+    /// Whether the N_VBUSEN pin decides if VBUS powers the system. OTG boards
+    /// use the pin to stop drawing from VBUS while they're supplying it.
+    /// Disabled, the VBUS path is used whatever the pin says. This is bit 7
+    /// of the register, which is set when the pin is ignored.
+    pub fn set_vbus_pin_control(&mut self, enabled: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::VbusControl as u8, |value| {
+            if enabled { value & !0x80 } else { value | 0x80 }
+        })
+    }
+
+    pub fn vbus_pin_control(&mut self) -> Result<bool, Error<E>> {
+        let value = self.get_8bit_register(Registers::VbusControl as u8)?;
+
+        Ok(value & 0x80 == 0)
+    }
+
+    /// Only the target voltage bits of the charge control register are
+    /// changed, the charge current and enable bits are preserved.
+    pub fn set_charge_target_voltage(&mut self, voltage: ChargeTargetVoltage) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ChargeControl as u8, |value| (value & !0x60) | voltage.to_register())
+    }
+
+    pub fn charge_target_voltage(&mut self) -> Result<ChargeTargetVoltage, Error<E>> {
+        let value = self.get_8bit_register(Registers::ChargeControl as u8)?;
+
+        Ok(ChargeTargetVoltage::from_register(value))
+    }
+
+    /// In milliamps, between 300mA and 1800mA in 100mA steps. Values outside
+    /// of that are clamped and only the low nibble of the charge control
+    /// register is changed.
+    pub fn set_charge_current_ma(&mut self, ma: u16) -> Result<(), Error<E>> {
+        let steps = conversions::charge_current_to_register(ma);
+        self.modify_register(Registers::ChargeControl as u8, |value| (value & 0xf0) | steps)
     }
 
     /// In milliamps
-    pub fn vbus_current(&mut self) -> Result<u16, E> {
-        let mut value = self.get_adc_12bits(Registers::VbusCurrent as u8)?;
+    pub fn charge_current_ma(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::ChargeControl as u8)?;
 
-        // Trying to avoid too much rounding as it's multiples of 0.375 milliamps
-        // The max this register will return is 4096, so we have enough headroom
-        // to multiply by 16, and 0.375*16 (probably by design) comes out as 6.
-        value = ((value * 16) / 6) / 16;
+        Ok(conversions::charge_current_ma(value))
+    }
 
-        Ok(value / 2)
+    /// Master switch for battery charging. The target voltage and current
+    /// stay configured while charging is off.
+    pub fn set_charging_enabled(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ChargeControl as u8, |value| {
+            if on { value | 0x80 } else { value & !0x80 }
+        })
     }
 
-    /// In celcius
-    pub fn temperature(&mut self) -> Result<i16, E> {
-        // Check out page 25 of the datasheet for the weird math
+    pub fn charging_enabled(&mut self) -> Result<bool, Error<E>> {
+        let value = self.get_8bit_register(Registers::ChargeControl as u8)?;
 
-        let value = self.get_adc_12bits(Registers::Temperature as u8)?;
+        Ok(value & 0x80 != 0)
+    }
 
-        let mut value = value as i16 / 10;
-        value -= 145;
+    /// In millivolts on the TS pin. Charging stops once the pin drops below
+    /// this, since the battery is too hot.
+    pub fn set_charge_temp_high_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_ts_threshold_mv(Registers::ChargeTempHigh, mv)
+    }
 
-        Ok(value)
+    pub fn charge_temp_high_mv(&mut self) -> Result<u16, Error<E>> {
+        self.ts_threshold_mv(Registers::ChargeTempHigh)
     }
 
-    /// In millivolts. Battery temperature sensor
-    pub fn ts_voltage(&mut self) -> Result<u16, E> {
-        let value = self.get_adc_12bits(Registers::BatteryTemperature as u8)?;
+    /// In millivolts on the TS pin. Charging stops once the pin rises above
+    /// this, since the battery is too cold.
+    pub fn set_charge_temp_low_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_ts_threshold_mv(Registers::ChargeTempLow, mv)
+    }
 
-        // Increments of 0.8
-        Ok((value * 8) / 10)
+    pub fn charge_temp_low_mv(&mut self) -> Result<u16, Error<E>> {
+        self.ts_threshold_mv(Registers::ChargeTempLow)
     }
 
-    /// In millivolts. I'm assuming power division is 1.4 as defined in APS, but
-    /// as there is nothing in the datasheet specifically for Ipsout's settings
-    /// and there is no register defined for ipsout.
-    pub fn ipsout_voltage(&mut self) -> Result<u16, E> {
-        let value = self.get_adc_12bits(Registers::SystemIpsout as u8)?;
+    /// Same as `set_charge_temp_high_mv()`, but for when running off the battery
+    pub fn set_discharge_temp_high_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_ts_threshold_mv(Registers::DischargeTempHigh, mv)
+    }
 
-        // Increments of 1.4
-        Ok((value * 14) / 10)
+    pub fn discharge_temp_high_mv(&mut self) -> Result<u16, Error<E>> {
+        self.ts_threshold_mv(Registers::DischargeTempHigh)
     }
 
-    /// In millivolts. Unconfirmed
-    pub fn gpio0_voltage(&mut self) -> Result<u16, E> {
-        let value = self.get_adc_12bits(Registers::Gpio0Voltage as u8)?;
+    /// Same as `set_charge_temp_low_mv()`, but for when running off the battery
+    pub fn set_discharge_temp_low_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_ts_threshold_mv(Registers::DischargeTempLow, mv)
+    }
 
-        Ok(value / 2)
+    pub fn discharge_temp_low_mv(&mut self) -> Result<u16, Error<E>> {
+        self.ts_threshold_mv(Registers::DischargeTempLow)
     }
 
-    /// In millivolts. Unconfirmed
-    pub fn gpio1_voltage(&mut self) -> Result<u16, E> {
-        let value = self.get_adc_12bits(Registers::Gpio1Voltage as u8)?;
+    /// In millivolts, between 2867mV and 4295mV in 5.6mV steps. Raises
+    /// `IrqStatus::APS_LOW_LEVEL1` once the system supply drops below it.
+    pub fn set_aps_warning_level1_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_8bit_register(Registers::ApsWarningLevel1 as u8, conversions::aps_warning_to_register(mv))
+    }
+
+    pub fn aps_warning_level1_mv(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::ApsWarningLevel1 as u8)?;
 
-        Ok(value / 2)
+        Ok(conversions::aps_warning_mv(value))
     }
 
-    // In percentage.
-    pub fn battery_level(&mut self) -> Result<u8, E> {
-        // The MSB for the voltage is a control bit that enables or
-        // disables sampling
-        match self.write_read_byte(Registers::BatteryLevel as u8) {
-            Ok(x) => Ok(x & 0b0111_1111),
-            Err(x) => Err(x)
-        }
+    /// Same as `set_aps_warning_level1_mv()`, for `IrqStatus::APS_LOW_LEVEL2`.
+    /// Usually set a bit below level 1 as the last warning before shutdown.
+    pub fn set_aps_warning_level2_mv(&mut self, mv: u16) -> Result<(), Error<E>> {
+        self.set_8bit_register(Registers::ApsWarningLevel2 as u8, conversions::aps_warning_to_register(mv))
     }
 
-    pub fn battery_present(&mut self) -> Result<bool, E> {
-        let level = self.battery_level()?;
+    pub fn aps_warning_level2_mv(&mut self) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(Registers::ApsWarningLevel2 as u8)?;
 
-        Ok(level == BATTERY_LEVEL_MISSING)
+        Ok(conversions::aps_warning_mv(value))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    extern crate linux_embedded_hal as linux_hal;
+    fn set_ts_threshold_mv(&mut self, register: Registers, mv: u16) -> Result<(), Error<E>> {
+        self.set_8bit_register(register as u8, conversions::ts_threshold_to_register(mv))
+    }
 
-    use super::*;
+    fn ts_threshold_mv(&mut self, register: Registers) -> Result<u16, Error<E>> {
+        let value = self.get_8bit_register(register as u8)?;
+
+        Ok(conversions::ts_threshold_mv(value))
+    }
 
-    use self::linux_hal::{Pin, I2cdev};
-    use hal::digital::OutputPin;
+    /// How long charging may spend in the pre-charge and constant current
+    /// stages before giving up. The CHGLED and reserved bits sharing the
+    /// register are left alone.
+    pub fn set_charge_timeouts(&mut self, timeouts: ChargeTimeouts) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ChargeControl2 as u8, |value| {
+            (value & 0b0011_1100) | timeouts.to_register()
+        })
+    }
 
-    #[test]
-    fn permissions() {
-        let mut gpio = Pin::new(135);
-        let state = gpio.is_low();
+    pub fn charge_timeouts(&mut self) -> Result<ChargeTimeouts, Error<E>> {
+        let value = self.get_8bit_register(Registers::ChargeControl2 as u8)?;
 
-        if state {
-            gpio.set_high();
-        } else {
-            gpio.set_low();
-        }
+        Ok(ChargeTimeouts::from_register(value))
     }
 
-    #[test]
-    fn battery_level() {
-        let i2c = I2cdev::new("/dev/i2c-0").unwrap();
-        let address = 0x34;
+    /// Usually needs doing once at boot for boards with an RTC backup cell
+    pub fn set_backup_battery_charging(&mut self, config: BackupBatteryConfig) -> Result<(), Error<E>> {
+        self.modify_register(Registers::BackupBatteryControl as u8, |value| {
+            (value & 0b1_1100) | config.to_register()
+        })
+    }
 
-        let mut pmic = Axp209::new(i2c, address);
-        let _level = pmic.battery_level().unwrap();
+    pub fn backup_battery_config(&mut self) -> Result<BackupBatteryConfig, Error<E>> {
+        let value = self.get_8bit_register(Registers::BackupBatteryControl as u8)?;
 
-        // Values for 'level' can be either the percentage, or
-        // 0x7F if the battery is missing
+        Ok(BackupBatteryConfig::from_register(value))
+    }
+
+    /// The PWROK delay sharing the register is left alone
+    pub fn set_pek_config(&mut self, config: PekConfig) -> Result<(), Error<E>> {
+        self.modify_register(Registers::PekConfig as u8, |value| (value & 0b100) | config.to_register())
+    }
+
+    pub fn pek_config(&mut self) -> Result<PekConfig, Error<E>> {
+        let value = self.get_8bit_register(Registers::PekConfig as u8)?;
+
+        Ok(PekConfig::from_register(value))
+    }
+
+    /// The rest of the PEK register is left alone
+    pub fn set_pwrok_delay(&mut self, delay: PwrokDelay) -> Result<(), Error<E>> {
+        self.modify_register(Registers::PekConfig as u8, |value| (value & !0b100) | delay.to_register())
+    }
+
+    pub fn pwrok_delay(&mut self) -> Result<PwrokDelay, Error<E>> {
+        let value = self.get_8bit_register(Registers::PekConfig as u8)?;
+
+        Ok(PwrokDelay::from_register(value))
+    }
+
+    /// Reads all five IRQ enable registers in one transaction
+    pub fn irq_enable(&mut self) -> Result<IrqEnable, Error<E>> {
+        Ok(IrqEnable::from_registers(self.read_registers(Registers::IrqEnable as u8)?))
+    }
+
+    /// Writes all five IRQ enable registers, so anything not enabled in
+    /// `value` gets disabled.
+    pub fn set_irq_enable(&mut self, value: IrqEnable) -> Result<(), Error<E>> {
+        self.set_8bit_registers(Registers::IrqEnable as u8, &value.to_registers())
+    }
+
+    /// Flashes CHGLED at 1Hz under manual control and enables the level 1
+    /// APS warning IRQ, so whoever handles it knows the LED is telling the
+    /// user to plug in. The chip can't tie the LED to the warning by itself,
+    /// so it flashes from the moment this is called. Turning it off hands
+    /// CHGLED back to the charger and disables the IRQ again.
+    pub fn set_low_power_indicator(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::ShutdownControl as u8, |value| {
+            if on {
+                (value & !0x30) | ChgLedMode::Flash1Hz.to_register() | 0x08
+            } else {
+                (value & !0x38) | ChgLedMode::HighImpedance.to_register()
+            }
+        })?;
+
+        let irqs = self.irq_enable()?;
+        let irqs = if on {
+            irqs.enable(IrqStatus::APS_LOW_LEVEL1)
+        } else {
+            irqs.disable(IrqStatus::APS_LOW_LEVEL1)
+        };
+
+        self.set_irq_enable(irqs)
+    }
+
+    /// Reads all five IRQ status registers in one transaction
+    pub fn irq_status(&mut self) -> Result<IrqStatus, Error<E>> {
+        Ok(IrqStatus::from_registers(self.read_registers(Registers::IrqStatus as u8)?))
+    }
+
+    /// Whether ACIN or VBUS has gone over voltage since the events were
+    /// last acknowledged. There's no live over-voltage bit in the status
+    /// registers, only these latched events in 0x48 (bits 7 and 4), which
+    /// are left pending.
+    pub fn over_voltage(&mut self) -> Result<bool, Error<E>> {
+        let value = self.get_8bit_register(Registers::IrqStatus as u8)?;
+        let status = IrqStatus::from_registers([value, 0, 0, 0, 0]);
+
+        Ok(status.intersects(IrqStatus::ACIN_OVERVOLTAGE | IrqStatus::VBUS_OVERVOLTAGE))
+    }
+
+    /// Works out what turned the system on from the latched IRQ events, so
+    /// it has to be called before anything acknowledges them (this includes
+    /// the kernel's AXP20X driver). Nothing is acknowledged here.
+    pub fn power_on_source(&mut self) -> Result<PowerOnSource, Error<E>> {
+        Ok(PowerOnSource::from_irq_status(self.irq_status()?))
+    }
+
+    /// Acknowledges every pending IRQ by writing 1s to all of the status bits
+    pub fn clear_all(&mut self) -> Result<(), Error<E>> {
+        self.set_8bit_registers(Registers::IrqStatus as u8, &[0xff; 5])
+    }
+
+    /// Returns whichever of the events in `mask` are pending and acknowledges
+    /// just those, so other events stay pending for whoever handles them.
+    /// Handy for polling the power button without an IRQ line.
+    pub fn take_pending(&mut self, mask: IrqStatus) -> Result<IrqStatus, Error<E>> {
+        let pending = self.irq_status()? & mask;
+
+        // Writing 0 to a status bit leaves it alone
+        self.set_8bit_registers(Registers::IrqStatus as u8, &pending.to_registers())?;
+
+        Ok(pending)
+    }
+
+    /// Whether the power key was short pressed since the last check. The
+    /// event is acknowledged, a pending long press is left alone.
+    pub fn power_key_short_pressed(&mut self) -> Result<bool, Error<E>> {
+        Ok(!self.take_pending(IrqStatus::PEK_SHORT_PRESS)?.is_empty())
+    }
+
+    /// Same as `power_key_short_pressed()`, for a long press
+    pub fn power_key_long_pressed(&mut self) -> Result<bool, Error<E>> {
+        Ok(!self.take_pending(IrqStatus::PEK_LONG_PRESS)?.is_empty())
+    }
+
+    /// Checks for and acknowledges the APS low voltage warnings, leaving
+    /// every other event pending. Polling this is enough to shut down
+    /// gracefully on a board without the IRQ line wired up.
+    pub fn poll_low_battery(&mut self) -> Result<LowBatteryEvent, Error<E>> {
+        let pending = self.take_pending(IrqStatus::APS_LOW_LEVEL1 | IrqStatus::APS_LOW_LEVEL2)?;
+
+        Ok(LowBatteryEvent::from_irq_status(pending))
+    }
+
+    /// In milliamps
+    pub fn battery_discharging_current(&mut self) -> Result<Milliamps, Error<E>> {
+        let value = self.raw_battery_discharge_current()?;
+
+        Ok(self.calibration.battery_discharge_current.milliamps(Milliamps(conversions::battery_current(value))))
+    }
+
+    /// The unscaled 13 bit discharge current, in steps of
+    /// `BATTERY_CURRENT_LSB_UA`
+    pub fn raw_battery_discharge_current(&mut self) -> Result<u16, Error<E>> {
+        let mut recv: [u8; 2] = [ 0, 0 ];
+
+        self.get_8bit_registers(Registers::BatteryDischargeCurrent as u8, &mut recv)?;
+
+        Ok(conversions::adc_13bits(&recv))
+    }
+
+    /// In milliamps, positive while charging and negative while discharging.
+    /// The direction comes from `power_status()`, so only the current
+    /// matching it is read.
+    pub fn battery_net_current_ma(&mut self) -> Result<i32, Error<E>> {
+        if self.power_status()?.charging() {
+            Ok(self.battery_charging_current()?.0 as i32)
+        } else {
+            Ok(-(self.battery_discharging_current()?.0 as i32))
+        }
+    }
+
+    /// The unscaled 24 bit battery power value. The chip multiplies battery
+    /// voltage and current for us, so each step is 2 * 1.1mV * 0.5mA, or 1.1uW.
+    pub fn raw_battery_power(&mut self) -> Result<u32, Error<E>> {
+        let mut recv: [u8; 3] = [ 0, 0, 0 ];
+
+        self.get_8bit_registers(Registers::InstantaneousBatteryPower as u8, &mut recv)?;
+
+        Ok(u32::from_be_bytes([0, recv[0], recv[1], recv[2]]))
+    }
+
+    /// In microwatts
+    pub fn battery_instantaneous_power(&mut self) -> Result<u32, Error<E>> {
+        let value = self.raw_battery_power()?;
+
+        // 24 bits times 11 still fits comfortably in a u32
+        Ok(value * 11 / 10)
+    }
+
+    /// In milliwatts, rounded down. Each step is 1.1uW, or 0.0011mW.
+    pub fn battery_power_mw(&mut self) -> Result<u32, Error<E>> {
+        let value = self.raw_battery_power()?;
+
+        Ok(value * 11 / 10_000)
+    }
+
+    /// In millivolts
+    pub fn battery_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        let value = self.get_adc_12bits(Registers::BatteryVoltage as u8)?;
+
+        Ok(self.calibration.battery_voltage.millivolts(Millivolts(conversions::battery_voltage(value))))
+    }
+
+    /// In microvolts, exactly as the chip measured it. `battery_voltage()`
+    /// is this rounded to the nearest millivolt. No calibration is applied.
+    pub fn battery_voltage_microvolts(&mut self) -> Result<u32, Error<E>> {
+        let value = self.get_adc_12bits(Registers::BatteryVoltage as u8)?;
+
+        Ok(conversions::battery_voltage_uv(value))
+    }
+
+    /// In milliamps
+    pub fn battery_charging_current(&mut self) -> Result<Milliamps, Error<E>> {
+        let value = self.raw_battery_charge_current()?;
+
+        Ok(self.calibration.battery_charge_current.milliamps(Milliamps(conversions::battery_current(value))))
+    }
+
+    /// The unscaled 12 bit charge current, in steps of
+    /// `BATTERY_CURRENT_LSB_UA`. Unlike the discharge current this really
+    /// is 12 bits: the datasheet only puts four low bits in 0x7b, topping
+    /// out at 2047.5mA, while 0x7d has five for up to 4095.5mA.
+    pub fn raw_battery_charge_current(&mut self) -> Result<u16, Error<E>> {
+        self.get_adc_12bits(Registers::BatteryChargeCurrent as u8)
+    }
+
+    /// In millivolts
+    pub fn acin_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        let value = self.get_adc_12bits(Registers::AcinVoltage as u8)?;
+
+        Ok(self.calibration.acin_voltage.millivolts(Millivolts(conversions::acin_voltage(value))))
+    }
+
+    /// In milliamps
+    pub fn acin_current(&mut self) -> Result<Milliamps, Error<E>> {
+        let value = self.get_adc_12bits(Registers::AcinCurrent as u8)?;
+
+        Ok(self.calibration.acin_current.milliamps(Milliamps(conversions::acin_current(value))))
+    }
+
+    /// In millivolts
+    pub fn vbus_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        let value = self.get_adc_12bits(Registers::VbusVoltage as u8)?;
+
+        Ok(self.calibration.vbus_voltage.millivolts(Millivolts(conversions::vbus_voltage(value))))
+    }
+
+    /// In milliamps
+    pub fn vbus_current(&mut self) -> Result<Milliamps, Error<E>> {
+        let value = self.get_adc_12bits(Registers::VbusCurrent as u8)?;
+
+        Ok(self.calibration.vbus_current.milliamps(Milliamps(conversions::vbus_current(value))))
+    }
+
+    /// In celcius, rounded towards zero. See `temperature_millicelsius()`
+    /// for the full precision.
+    pub fn temperature(&mut self) -> Result<Celsius, Error<E>> {
+        Ok(Celsius((self.temperature_millicelsius()? / 1000) as i16))
+    }
+
+    /// In fahrenheit, rounded to the nearest degree (in either direction, so
+    /// -228.46F comes out as -228F)
+    pub fn temperature_fahrenheit(&mut self) -> Result<i16, Error<E>> {
+        let millifahrenheit = self.temperature_millicelsius()? * 9 / 5 + 32_000;
+
+        // Dividing rounds towards zero, so push away from it by half first
+        let rounding = if millifahrenheit < 0 { -500 } else { 500 };
+
+        Ok(((millifahrenheit + rounding) / 1000) as i16)
+    }
+
+    /// In thousandths of a degree celcius, though the chip only measures in
+    /// tenths of a degree.
+    pub fn temperature_millicelsius(&mut self) -> Result<i32, Error<E>> {
+        let value = self.get_adc_12bits(Registers::Temperature as u8)?;
+
+        Ok(conversions::temperature_millicelsius(value))
+    }
+
+    /// In millivolts. Battery temperature sensor
+    pub fn ts_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        let value = self.get_adc_12bits(Registers::BatteryTemperature as u8)?;
+
+        Ok(self.calibration.ts_voltage.millivolts(Millivolts(conversions::ts_voltage(value))))
+    }
+
+    /// The resistance of whatever is on the TS pin (usually the battery's
+    /// thermistor) in ohms, for anyone with their own thermistor curve.
+    /// `ts_current_ua` is the current the TS pin is set to source, in
+    /// microamps. A current of 0 gives `Error::InvalidData`.
+    pub fn battery_ts_resistance_ohms(&mut self, ts_current_ua: u16) -> Result<u32, Error<E>> {
+        let value = self.get_adc_12bits(Registers::BatteryTemperature as u8)?;
+
+        conversions::ts_resistance_ohms(value, ts_current_ua).ok_or(Error::InvalidData)
+    }
+
+    /// In millivolts. I'm assuming power division is 1.4 as defined in APS, but
+    /// as there is nothing in the datasheet specifically for Ipsout's settings
+    /// and there is no register defined for ipsout.
+    pub fn ipsout_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        let value = self.get_adc_12bits(Registers::SystemIpsout as u8)?;
+
+        Ok(self.calibration.ipsout_voltage.millivolts(Millivolts(conversions::ipsout_voltage(value))))
+    }
+
+    /// In millivolts. The datasheet calls 0x7e/0x7f the APS voltage, which
+    /// is the IPSOUT rail under another name, so this reads the same ADC
+    /// channel as `ipsout_voltage()`.
+    pub fn aps_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        self.ipsout_voltage()
+    }
+
+    /// In millivolts. Takes the pin's input range (see `gpio_adc_range()`)
+    /// into account, which costs an extra read.
+    pub fn gpio0_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        let value = self.get_adc_12bits(Registers::Gpio0Voltage as u8)?;
+        let range = self.gpio_adc_range(Gpio::Gpio0)?;
+        let mv = Millivolts(conversions::gpio_voltage(value, range == GpioAdcRange::High));
+
+        Ok(self.calibration.gpio0_voltage.millivolts(mv))
+    }
+
+    /// In millivolts. Same as `gpio0_voltage()`
+    pub fn gpio1_voltage(&mut self) -> Result<Millivolts, Error<E>> {
+        let value = self.get_adc_12bits(Registers::Gpio1Voltage as u8)?;
+        let range = self.gpio_adc_range(Gpio::Gpio1)?;
+        let mv = Millivolts(conversions::gpio_voltage(value, range == GpioAdcRange::High));
+
+        Ok(self.calibration.gpio1_voltage.millivolts(mv))
+    }
+
+    /// Only GPIO0 and GPIO1 can be sampled, the other pins give
+    /// `Error::InvalidData` without touching the chip
+    pub fn set_gpio_adc_range(&mut self, gpio: Gpio, range: GpioAdcRange) -> Result<(), Error<E>> {
+        let bit = gpio.adc_range_bit().ok_or(Error::InvalidData)?;
+
+        self.modify_register(Registers::GpioAdcRange as u8, |value| {
+            match range {
+                GpioAdcRange::Low => value & !bit,
+                GpioAdcRange::High => value | bit,
+            }
+        })
+    }
+
+    pub fn gpio_adc_range(&mut self, gpio: Gpio) -> Result<GpioAdcRange, Error<E>> {
+        let bit = gpio.adc_range_bit().ok_or(Error::InvalidData)?;
+        let value = self.get_8bit_register(Registers::GpioAdcRange as u8)?;
+
+        if value & bit != 0 {
+            Ok(GpioAdcRange::High)
+        } else {
+            Ok(GpioAdcRange::Low)
+        }
+    }
+
+    /// Reads the battery, ACIN, VBUS and temperature values in three
+    /// transactions rather than one per value
+    pub fn measurements(&mut self) -> Result<Measurements, Error<E>> {
+        // ACIN voltage through to the internal temperature are back to back
+        let mut power: [u8; 10] = [0; 10];
+        self.get_8bit_registers(Registers::AcinVoltage as u8, &mut power)?;
+
+        // As are battery voltage, charge current and discharge current
+        let mut battery: [u8; 6] = [0; 6];
+        self.get_8bit_registers(Registers::BatteryVoltage as u8, &mut battery)?;
+
+        let level = self.battery_level()?;
+
+        Ok(self.calibration.measurements(conversions::measurements(&power, &battery, level)))
+    }
+
+    /// ACIN, VBUS, the internal temperature and the TS pin in a single
+    /// transaction
+    pub fn read_adc_block(&mut self) -> Result<AdcBlock, Error<E>> {
+        let recv: [u8; 14] = self.read_registers(Registers::AcinVoltage as u8)?;
+
+        Ok(self.calibration.adc_block(conversions::adc_block(&recv)))
+    }
+
+    /// Borrows the driver for a loop of `measurements()`, see `monitor`
+    pub fn monitor(&mut self) -> Monitor<'_, I2C> {
+        Monitor::new(self)
+    }
+
+    /// `measurements()` along with the power and charging status, ready to
+    /// be printed while debugging
+    pub fn summary(&mut self) -> Result<PmicSummary, Error<E>> {
+        Ok(PmicSummary {
+            measurements: self.measurements()?,
+            power_status: self.power_status()?,
+            charging_status: self.charging_status()?,
+        })
+    }
+
+    /// Not every pin supports every mode (see `GpioMode`), asking for one
+    /// that isn't supported gives `Error::InvalidData` without touching the
+    /// chip.
+    pub fn set_gpio_mode(&mut self, gpio: Gpio, mode: GpioMode) -> Result<(), Error<E>> {
+        let bits = gpio.encode_mode(mode).ok_or(Error::InvalidData)?;
+        let mask = gpio.mode_mask();
+
+        self.modify_register(gpio.control_register() as u8, |value| (value & !mask) | bits)
+    }
+
+    pub fn gpio_mode(&mut self, gpio: Gpio) -> Result<GpioMode, Error<E>> {
+        let value = self.get_8bit_register(gpio.control_register() as u8)?;
+
+        Ok(gpio.decode_mode(value))
+    }
+
+    /// Reads the level on a pin configured as an input
+    pub fn gpio_read(&mut self, gpio: Gpio) -> Result<bool, Error<E>> {
+        let (register, bit) = gpio.input_state();
+        let value = self.get_8bit_register(register as u8)?;
+
+        Ok(value & bit != 0)
+    }
+
+    /// Drives a pin as an output. The output level is part of the pin's
+    /// function on this chip, so this changes the pin's control register and
+    /// leaves the other pins alone. GPIO2 and GPIO3 can only sink current, so
+    /// "high" leaves them floating.
+    pub fn gpio_write(&mut self, gpio: Gpio, high: bool) -> Result<(), Error<E>> {
+        self.set_gpio_mode(gpio, gpio.output_mode(high))
+    }
+
+    /// Raw value of the coulomb counter's charge accumulator
+    pub fn coulomb_charge(&mut self) -> Result<u32, Error<E>> {
+        self.get_32bit_register(Registers::CoulombBattery as u8)
+    }
+
+    /// Raw value of the coulomb counter's discharge accumulator
+    pub fn coulomb_discharge(&mut self) -> Result<u32, Error<E>> {
+        self.get_32bit_register(Registers::CoulombBatteryDischarge as u8)
+    }
+
+    /// In milliamp hours. How much charge went into the battery minus how much
+    /// came out since the counter was last cleared, so it goes negative if
+    /// the battery has been drained more than it's been charged.
+    pub fn coulomb_counter_mah(&mut self) -> Result<i32, Error<E>> {
+        let charge = self.coulomb_charge()? as i64;
+        let discharge = self.coulomb_discharge()? as i64;
+
+        // The accumulators count in steps of 65536 * 0.5mA per ADC sample,
+        // so the datasheet has us divide by the sample rate as
+        // well as the 3600 seconds in an hour.
+        let rate = self.adc_sample_rate()?.hz() as i64;
+
+        Ok(((charge - discharge) * 32768 / (3600 * rate)) as i32)
+    }
+
+    /// A battery percentage from the coulomb counter, which has to have been
+    /// cleared with `coulomb_counter_reset()` while the battery was flat.
+    /// Anything over `full_capacity_mah` counts as 100% and anything below
+    /// zero (the battery drained further than when the counter was cleared)
+    /// as 0%. A capacity of 0 gives `Error::InvalidData`.
+    pub fn coulomb_soc(&mut self, full_capacity_mah: u16) -> Result<u8, Error<E>> {
+        if full_capacity_mah == 0 {
+            return Err(Error::InvalidData);
+        }
+
+        let mah = self.coulomb_counter_mah()?;
+        let percent = mah as i64 * 100 / full_capacity_mah as i64;
+
+        Ok(percent.clamp(0, 100) as u8)
+    }
+
+    /// Remembers the battery's capacity for `rated_coulomb_soc()`. Nothing
+    /// is written to the chip.
+    pub fn set_coulomb_config(&mut self, config: CoulombConfig) {
+        self.coulomb = Some(config);
+    }
+
+    pub fn coulomb_config(&self) -> Option<CoulombConfig> {
+        self.coulomb
+    }
+
+    /// `coulomb_soc()` using the capacity from `set_coulomb_config()`, or
+    /// `Error::InvalidData` if there isn't one yet
+    pub fn rated_coulomb_soc(&mut self) -> Result<u8, Error<E>> {
+        let config = self.coulomb.ok_or(Error::InvalidData)?;
+
+        self.coulomb_soc(config.rated_capacity_mah())
+    }
+
+    /// Writes the enable, pause and clear bits in one go. Not to be confused
+    /// with `set_coulomb_config()`, which only keeps the battery capacity on
+    /// the driver.
+    pub fn set_coulomb_control(&mut self, control: CoulombControl) -> Result<(), Error<E>> {
+        self.modify_register(Registers::CoulombBatteryEncryption as u8, |value| {
+            (value & !CoulombControl::MASK) | control.to_register()
+        })
+    }
+
+    pub fn coulomb_control(&mut self) -> Result<CoulombControl, Error<E>> {
+        let value = self.get_8bit_register(Registers::CoulombBatteryEncryption as u8)?;
+
+        Ok(CoulombControl::from_register(value))
+    }
+
+    /// Start or stop the coulomb counter
+    pub fn coulomb_counter_enable(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::CoulombBatteryEncryption as u8, |value| {
+            if on { value | 0x80 } else { value & !0x80 }
+        })
+    }
+
+    /// Pause the coulomb counter without clearing the accumulators
+    pub fn coulomb_counter_pause(&mut self, paused: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::CoulombBatteryEncryption as u8, |value| {
+            if paused { value | 0x40 } else { value & !0x40 }
+        })
+    }
+
+    /// Zero both accumulators, so `coulomb_charge()` and `coulomb_discharge()`
+    /// read back as 0 afterwards. The chip clears the reset bit by itself
+    /// once it's done, so there's no need to write it back to 0.
+    pub fn coulomb_counter_reset(&mut self) -> Result<(), Error<E>> {
+        self.modify_register(Registers::CoulombBatteryEncryption as u8, |value| value | 0x20)
+    }
+
+    /// In percentage, or `BATTERY_LEVEL_MISSING` without a battery. Anything
+    /// else above 100% is reported as `Error::InvalidData`.
+    pub fn battery_level(&mut self) -> Result<u8, Error<E>> {
+        let value = self.write_read_byte(Registers::BatteryLevel as u8)?;
+
+        conversions::battery_level(value).ok_or(Error::InvalidData)
+    }
+
+    /// Same as `battery_level()`, but with `None` when there's no battery so
+    /// `BATTERY_LEVEL_MISSING` can't be mistaken for 127%
+    pub fn battery_level_opt(&mut self) -> Result<Option<u8>, Error<E>> {
+        let level = self.battery_level()?;
+
+        Ok(Some(level).filter(|&level| level != BATTERY_LEVEL_MISSING))
+    }
+
+    /// How long the battery lasts at the current discharge rate, from the
+    /// battery level and `capacity_mah`. Nothing being drawn gives
+    /// `u32::MAX`, and no battery gives `Error::InvalidData`.
+    pub fn estimated_runtime_minutes(&mut self, capacity_mah: u16) -> Result<u32, Error<E>> {
+        let level = self.battery_level_opt()?.ok_or(Error::InvalidData)?;
+        let discharge_ma = self.battery_discharging_current()?.0 as u32;
+
+        if discharge_ma == 0 {
+            return Ok(u32::MAX);
+        }
+
+        let remaining_mah = capacity_mah as u32 * level as u32 / 100;
+
+        Ok(remaining_mah * 60 / discharge_ma)
+    }
+
+    /// The top bit of the battery level register turns the fuel gauge on
+    /// and off. Some boards ship with it off, which leaves the level at 0.
+    /// The percentage bits are written back as they were read.
+    pub fn set_battery_level_sampling(&mut self, on: bool) -> Result<(), Error<E>> {
+        self.modify_register(Registers::BatteryLevel as u8, |value| {
+            if on { value | 0x80 } else { value & !0x80 }
+        })
+    }
+
+    pub fn battery_level_sampling_enabled(&mut self) -> Result<bool, Error<E>> {
+        let value = self.get_8bit_register(Registers::BatteryLevel as u8)?;
+
+        Ok(value & 0x80 != 0)
+    }
+
+    /// Based on the battery level, which reads 0x7f without a battery. The
+    /// battery present bit from `charging_status()` is more reliable.
+    pub fn battery_present(&mut self) -> Result<bool, Error<E>> {
+        let level = self.battery_level()?;
+
+        Ok(level != BATTERY_LEVEL_MISSING)
+    }
+
+    /// A battery percentage worked out from its voltage, for packs where
+    /// `battery_level()` isn't much use. `curve` maps millivolts to percent
+    /// and has to be sorted by voltage; readings in between points are
+    /// interpolated and anything beyond either end is clamped to it. An
+    /// empty curve gives `Error::InvalidData`.
+    ///
+    /// ```ignore
+    ///     let level = pmic.soc_from_voltage(&[(3300, 0), (3700, 50), (4200, 100)])?;
+    /// ```
+    pub fn soc_from_voltage(&mut self, curve: &[(u16, u8)]) -> Result<u8, Error<E>> {
+        let voltage = self.battery_voltage()?;
+
+        conversions::soc_from_voltage(voltage.0, curve).ok_or(Error::InvalidData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate embedded_hal_mock;
+
+    use super::*;
+
+    use core::convert::Infallible;
+
+    use hal::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Operation};
+
+    use self::embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction};
+
+    /// Stands in for the chip by keeping a copy of its register map. Reads
+    /// auto-increment the address like the real thing does. Multi-byte
+    /// writes do too, which the chip doesn't, but the driver never sends
+    /// those.
+    struct MockI2c {
+        registers: [u8; 256],
+    }
+
+    impl MockI2c {
+        fn new() -> Self {
+            MockI2c {
+                registers: [0; 256],
+            }
+        }
+
+        fn with(register: u8, value: u8) -> Self {
+            let mut mock = Self::new();
+            mock.registers[register as usize] = value;
+
+            mock
+        }
+    }
+
+    impl ErrorType for MockI2c {
+        type Error = Infallible;
+    }
+
+    impl I2c for MockI2c {
+        fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Infallible> {
+            assert_eq!(address, ADDRESS);
+
+            // The first byte written picks the register, the rest of the
+            // transaction carries on from there
+            let mut pointer = 0;
+            for operation in operations {
+                match operation {
+                    Operation::Write(bytes) => {
+                        pointer = bytes[0] as usize;
+                        for byte in &bytes[1..] {
+                            self.registers[pointer] = *byte;
+                            pointer += 1;
+                        }
+                    }
+                    Operation::Read(buffer) => {
+                        let len = buffer.len();
+                        buffer.copy_from_slice(&self.registers[pointer..pointer + len]);
+                        pointer += len;
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn register(pmic: &Axp209<MockI2c>, register: Registers) -> u8 {
+        pmic.device.registers[register as usize]
+    }
+
+    #[test]
+    fn rail_enable_only_touches_its_bit() {
+        // Reserved bits 7 and 5 set along with DCDC2 and EXTEN
+        let mut pmic = Axp209::new(MockI2c::with(Registers::PowerControl as u8, 0b1011_0001));
+
+        pmic.set_rail_enabled(Rail::Ldo3, true).unwrap();
+        assert!(pmic.rail_enabled(Rail::Ldo3).unwrap());
+        assert!(pmic.rail_enabled(Rail::Dcdc2).unwrap());
+        assert!(!pmic.rail_enabled(Rail::Ldo2).unwrap());
+
+        pmic.set_rail_enabled(Rail::Exten, false).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerControl), 0b1111_0000);
+    }
+
+    #[test]
+    fn output_control() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::PowerControl as u8, 0b1010_0001));
+        let rails = OutputControl::DCDC2 | OutputControl::DCDC3 | OutputControl::LDO3;
+
+        pmic.set_output_control(rails).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerControl), 0b1111_0010);
+        assert_eq!(pmic.output_control().unwrap(), rails);
+
+        pmic.set_output_control(OutputControl::empty()).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerControl), 0b1010_0000);
+        assert_eq!(pmic.output_control().unwrap(), OutputControl::empty());
+    }
+
+    #[test]
+    fn rail_bits() {
+        for &(rail, bit) in &[
+            (Rail::Dcdc2, 4),
+            (Rail::Dcdc3, 1),
+            (Rail::Ldo2, 2),
+            (Rail::Ldo3, 6),
+            (Rail::Ldo4, 3),
+            (Rail::Exten, 0),
+        ] {
+            let mut pmic = Axp209::new(MockI2c::new());
+            pmic.set_rail_enabled(rail, true).unwrap();
+
+            assert_eq!(register(&pmic, Registers::PowerControl), 1 << bit);
+        }
+    }
+
+    #[test]
+    fn dcdc2_voltage_rounding() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::Dcdc2Voltage as u8, 0xc0));
+
+        pmic.set_dcdc2_voltage_mv(712).unwrap();
+        assert_eq!(pmic.dcdc2_voltage_mv().unwrap(), 700);
+
+        pmic.set_dcdc2_voltage_mv(713).unwrap();
+        assert_eq!(pmic.dcdc2_voltage_mv().unwrap(), 725);
+
+        pmic.set_dcdc2_voltage_mv(500).unwrap();
+        assert_eq!(pmic.dcdc2_voltage_mv().unwrap(), 700);
+
+        pmic.set_dcdc2_voltage_mv(2280).unwrap();
+        assert_eq!(pmic.dcdc2_voltage_mv().unwrap(), 2275);
+
+        // Reserved bits survive
+        assert_eq!(register(&pmic, Registers::Dcdc2Voltage), 0xff);
+    }
+
+    #[test]
+    fn irq_status_decoding() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x48..0x4d].copy_from_slice(&[0x48, 0x08, 0x02, 0x02, 0x80]);
+        let mut pmic = Axp209::new(mock);
+
+        let status = pmic.irq_status().unwrap();
+        assert_eq!(status, IrqStatus::ACIN_CONNECTED
+            | IrqStatus::VBUS_CONNECTED
+            | IrqStatus::CHARGING_STARTED
+            | IrqStatus::PEK_SHORT_PRESS
+            | IrqStatus::APS_LOW_LEVEL1
+            | IrqStatus::TIMER_EXPIRED);
+        assert!(status.acin_connected());
+        assert!(status.pek_short_press());
+        assert!(!status.pek_long_press());
+        assert_eq!(status.to_registers(), [0x48, 0x08, 0x02, 0x02, 0x80]);
+
+        pmic.clear_all().unwrap();
+        assert_eq!(&pmic.device.registers[0x48..0x4d], &[0xff; 5]);
+    }
+
+    #[test]
+    fn take_pending_only_clears_masked_events() {
+        // Short and long press both pending, plus VBUS being plugged in
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x48], vec![0x08, 0x00, 0x03, 0x00, 0x00]),
+            Transaction::write(ADDRESS, vec![0x48, 0x00]),
+            Transaction::write(ADDRESS, vec![0x49, 0x00]),
+            Transaction::write(ADDRESS, vec![0x4a, 0x02]),
+            Transaction::write(ADDRESS, vec![0x4b, 0x00]),
+            Transaction::write(ADDRESS, vec![0x4c, 0x00]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        let taken = pmic.take_pending(IrqStatus::PEK_SHORT_PRESS | IrqStatus::APS_LOW_LEVEL1).unwrap();
+        assert_eq!(taken, IrqStatus::PEK_SHORT_PRESS);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn poll_low_battery() {
+        // Level 1 pending along with a short press
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x48], vec![0x00, 0x00, 0x02, 0x02, 0x00]),
+            Transaction::write(ADDRESS, vec![0x48, 0x00]),
+            Transaction::write(ADDRESS, vec![0x49, 0x00]),
+            Transaction::write(ADDRESS, vec![0x4a, 0x00]),
+            Transaction::write(ADDRESS, vec![0x4b, 0x02]),
+            Transaction::write(ADDRESS, vec![0x4c, 0x00]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        let event = pmic.poll_low_battery().unwrap();
+        assert!(event.warning_level1);
+        assert!(!event.warning_level2);
+        assert!(event.any());
+        pmic.free().done();
+
+        // Just level 2
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x48], vec![0x00, 0x00, 0x00, 0x01, 0x00]),
+            Transaction::write(ADDRESS, vec![0x48, 0x00]),
+            Transaction::write(ADDRESS, vec![0x49, 0x00]),
+            Transaction::write(ADDRESS, vec![0x4a, 0x00]),
+            Transaction::write(ADDRESS, vec![0x4b, 0x01]),
+            Transaction::write(ADDRESS, vec![0x4c, 0x00]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        assert_eq!(pmic.poll_low_battery().unwrap(), LowBatteryEvent { warning_level1: false, warning_level2: true });
+        pmic.free().done();
+
+        assert!(!LowBatteryEvent::from_irq_status(IrqStatus::PEK_SHORT_PRESS).any());
+    }
+
+    #[test]
+    fn power_key_presses() {
+        fn status(pek: u8) -> Transaction {
+            Transaction::write_read(ADDRESS, vec![0x48], vec![0x00, 0x00, pek, 0x00, 0x00])
+        }
+        fn acknowledge(pek: u8) -> std::vec::Vec<Transaction> {
+            [0x00, 0x00, pek, 0x00, 0x00].iter().enumerate()
+                .map(|(i, value)| Transaction::write(ADDRESS, vec![0x48 + i as u8, *value]))
+                .collect()
+        }
+
+        // Both pending: taking the short press leaves the long one alone
+        let mut transactions = vec![status(0x03)];
+        transactions.extend(acknowledge(0x02));
+        transactions.push(status(0x01));
+        transactions.extend(acknowledge(0x01));
+        // Only a long press, so no short press to report
+        transactions.push(status(0x01));
+        transactions.extend(acknowledge(0x00));
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        assert!(pmic.power_key_short_pressed().unwrap());
+        assert!(pmic.power_key_long_pressed().unwrap());
+        assert!(!pmic.power_key_short_pressed().unwrap());
+        pmic.free().done();
+    }
+
+    #[test]
+    fn over_voltage() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::IrqStatus as u8, 0b0110_1100));
+        assert!(!pmic.over_voltage().unwrap());
+
+        pmic.device.registers[Registers::IrqStatus as usize] = 0b1000_0000;
+        assert!(pmic.over_voltage().unwrap());
+
+        pmic.device.registers[Registers::IrqStatus as usize] = 0b0001_0000;
+        assert!(pmic.over_voltage().unwrap());
+        assert_eq!(register(&pmic, Registers::IrqStatus), 0b0001_0000);
+    }
+
+    #[test]
+    fn power_on_source() {
+        let mut pmic = Axp209::new(MockI2c::new());
+        let mut source = |registers: [u8; 5]| {
+            pmic.device.registers[0x48..0x4d].copy_from_slice(&registers);
+            let source = pmic.power_on_source().unwrap();
+
+            // Still latched for whoever wants them next
+            assert_eq!(&pmic.device.registers[0x48..0x4d], &registers);
+            source
+        };
+
+        assert_eq!(source([0x40, 0x00, 0x00, 0x00, 0x00]), PowerOnSource::Acin);
+        assert_eq!(source([0x08, 0x00, 0x00, 0x00, 0x00]), PowerOnSource::Vbus);
+        assert_eq!(source([0x00, 0x00, 0x02, 0x00, 0x00]), PowerOnSource::PowerKey);
+        assert_eq!(source([0x00, 0x00, 0x00, 0x00, 0x20]), PowerOnSource::PowerKey);
+        // Power plugged in long before the key was pressed
+        assert_eq!(source([0x48, 0x00, 0x01, 0x00, 0x00]), PowerOnSource::PowerKey);
+        // ACIN is picked over VBUS when both are connected
+        assert_eq!(source([0x48, 0x00, 0x00, 0x00, 0x00]), PowerOnSource::Acin);
+        assert_eq!(source([0x00, 0x80, 0x00, 0x00, 0x80]), PowerOnSource::Unknown);
+    }
+
+    #[test]
+    fn irq_enable_and_status_share_bits() {
+        let mut pmic = Axp209::new(MockI2c::new());
+
+        for &event in &[Irq::ACIN_OVERVOLTAGE, Irq::CHARGING_FINISHED, Irq::PEK_SHORT_PRESS, Irq::APS_LOW_LEVEL2, Irq::GPIO0_EDGE] {
+            pmic.set_irq_enable(IrqEnable::new().enable(event)).unwrap();
+            let mut enabled = [0; 5];
+            enabled.copy_from_slice(&pmic.device.registers[0x40..0x45]);
+
+            pmic.device.registers[0x48..0x4d].copy_from_slice(&enabled);
+            assert_eq!(pmic.irq_status().unwrap(), event);
+            assert_eq!(pmic.irq_enable().unwrap().events(), event);
+        }
+    }
+
+    #[test]
+    fn irq_enable_only_selected_events() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x40..0x45].copy_from_slice(&[0xd8, 0xff, 0x3b, 0xc3, 0x00]);
+        let mut pmic = Axp209::new(mock);
+
+        // The closest thing to battery undervoltage is the level 1 APS warning
+        let irqs = IrqEnable::new()
+            .enable(IrqStatus::PEK_SHORT_PRESS)
+            .enable(IrqStatus::APS_LOW_LEVEL1);
+        pmic.set_irq_enable(irqs).unwrap();
+
+        assert_eq!(&pmic.device.registers[0x40..0x45], &[0x00, 0x00, 0x02, 0x02, 0x00]);
+
+        let irqs = pmic.irq_enable().unwrap();
+        assert!(irqs.enabled(IrqStatus::PEK_SHORT_PRESS));
+        assert!(!irqs.enabled(IrqStatus::PEK_LONG_PRESS));
+        assert_eq!(irqs.events(), IrqStatus::PEK_SHORT_PRESS | IrqStatus::APS_LOW_LEVEL1);
+    }
+
+    #[test]
+    fn low_power_indicator() {
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x32], vec![0x46]),
+            Transaction::write(ADDRESS, vec![0x32, 0x5e]),
+            Transaction::write_read(ADDRESS, vec![0x40], vec![0x00, 0x00, 0x02, 0x00, 0x00]),
+            Transaction::write(ADDRESS, vec![0x40, 0x00]),
+            Transaction::write(ADDRESS, vec![0x41, 0x00]),
+            Transaction::write(ADDRESS, vec![0x42, 0x02]),
+            Transaction::write(ADDRESS, vec![0x43, 0x02]),
+            Transaction::write(ADDRESS, vec![0x44, 0x00]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        pmic.set_low_power_indicator(true).unwrap();
+        pmic.free().done();
+
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x32], vec![0x5e]),
+            Transaction::write(ADDRESS, vec![0x32, 0x46]),
+            Transaction::write_read(ADDRESS, vec![0x40], vec![0x00, 0x00, 0x02, 0x02, 0x00]),
+            Transaction::write(ADDRESS, vec![0x40, 0x00]),
+            Transaction::write(ADDRESS, vec![0x41, 0x00]),
+            Transaction::write(ADDRESS, vec![0x42, 0x02]),
+            Transaction::write(ADDRESS, vec![0x43, 0x00]),
+            Transaction::write(ADDRESS, vec![0x44, 0x00]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        pmic.set_low_power_indicator(false).unwrap();
+        pmic.free().done();
+    }
+
+    #[test]
+    fn safe_power_off() {
+        let transactions = [
+            Transaction::write(ADDRESS, vec![0x06, 0x01]),
+            Transaction::write(ADDRESS, vec![0x07, 0x02]),
+            Transaction::write(ADDRESS, vec![0x08, 0x03]),
+            Transaction::write(ADDRESS, vec![0x09, 0x04]),
+            Transaction::write(ADDRESS, vec![0x0a, 0x05]),
+            Transaction::write(ADDRESS, vec![0x0b, 0x06]),
+            // Charging on, 4.2V at 1200mA
+            Transaction::write_read(ADDRESS, vec![0x33], vec![0xc9]),
+            Transaction::write(ADDRESS, vec![0x33, 0x49]),
+            // Everything on
+            Transaction::write_read(ADDRESS, vec![0x12], vec![0x5f]),
+            Transaction::write(ADDRESS, vec![0x12, 0x16]),
+            Transaction::write_read(ADDRESS, vec![0x32], vec![0x46]),
+            Transaction::write(ADDRESS, vec![0x32, 0xc6]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        pmic.safe_power_off(&[1, 2, 3, 4, 5, 6]).unwrap();
+        pmic.free().done();
+    }
+
+    #[test]
+    fn shutdown_sets_only_bit_7() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ShutdownControl as u8, 0x46));
+
+        pmic.shutdown().unwrap();
+        assert_eq!(register(&pmic, Registers::ShutdownControl), 0xc6);
+    }
+
+    #[test]
+    fn battery_detection() {
+        // CHGLED flashing under manual control with a 2s N_OE delay
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ShutdownControl as u8, 0x1a));
+        assert!(!pmic.battery_detection_enabled().unwrap());
+
+        pmic.set_battery_detection(true).unwrap();
+        assert_eq!(register(&pmic, Registers::ShutdownControl), 0x5a);
+        assert!(pmic.battery_detection_enabled().unwrap());
+
+        pmic.set_battery_detection(false).unwrap();
+        assert_eq!(register(&pmic, Registers::ShutdownControl), 0x1a);
+    }
+
+    #[test]
+    fn battery_instantaneous_power() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x70..0x73].copy_from_slice(&[0x01, 0x86, 0xa0]);
+        let mut pmic = Axp209::new(mock);
+
+        // 100,000 steps of 1.1uW
+        assert_eq!(pmic.raw_battery_power().unwrap(), 100_000);
+        assert_eq!(pmic.battery_instantaneous_power().unwrap(), 110_000);
+        assert_eq!(pmic.battery_power_mw().unwrap(), 110);
+
+        // About 3.8V at 500mA
+        pmic.device.registers[0x70..0x73].copy_from_slice(&[0x1a, 0x46, 0x39]);
+        assert_eq!(pmic.raw_battery_power().unwrap(), 1_721_913);
+        assert_eq!(pmic.battery_power_mw().unwrap(), 1894);
+    }
+
+    #[test]
+    fn coulomb_counter() {
+        let mut mock = MockI2c::new();
+        mock.registers[0xb0..0xb8].copy_from_slice(&[
+            0x00, 0x00, 0x2b, 0xf2,  // 11250
+            0x12, 0x34, 0x56, 0x78,
+        ]);
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(pmic.coulomb_charge().unwrap(), 11250);
+        assert_eq!(pmic.coulomb_discharge().unwrap(), 0x1234_5678);
+
+        pmic.device.registers[0xb4..0xb8].copy_from_slice(&[0; 4]);
+        assert_eq!(pmic.coulomb_counter_mah().unwrap(), 4096);
+
+        // Flip it around at 100Hz
+        pmic.device.registers[0xb0..0xb8].copy_from_slice(&[0, 0, 0, 0, 0x00, 0x00, 0x2b, 0xf2]);
+        pmic.device.registers[Registers::AdcSpeed as usize] = 0b1000_0000;
+        assert_eq!(pmic.coulomb_counter_mah().unwrap(), -1024);
+    }
+
+    #[test]
+    fn coulomb_soc() {
+        // 4096mAh in at 25Hz
+        let mut mock = MockI2c::new();
+        mock.registers[0xb0..0xb4].copy_from_slice(&[0x00, 0x00, 0x2b, 0xf2]);
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(pmic.coulomb_soc(8192).unwrap(), 50);
+        assert_eq!(pmic.coulomb_soc(2000).unwrap(), 100);
+        assert_eq!(pmic.coulomb_soc(0), Err(Error::InvalidData));
+
+        // More out than in
+        pmic.device.registers[0xb0..0xb8].copy_from_slice(&[0, 0, 0, 0, 0x00, 0x00, 0x2b, 0xf2]);
+        assert_eq!(pmic.coulomb_soc(8192).unwrap(), 0);
+    }
+
+    #[test]
+    fn coulomb_config() {
+        let mut mock = MockI2c::new();
+        mock.registers[0xb0..0xb4].copy_from_slice(&[0x00, 0x00, 0x2b, 0xf2]);
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(pmic.coulomb_config(), None);
+        assert_eq!(pmic.rated_coulomb_soc(), Err(Error::InvalidData));
+
+        pmic.set_coulomb_config(CoulombConfig::new(8192));
+        assert_eq!(pmic.coulomb_config().unwrap().rated_capacity_mah(), 8192);
+        assert_eq!(pmic.rated_coulomb_soc().unwrap(), 50);
+
+        pmic.set_coulomb_config(CoulombConfig::new(0));
+        assert_eq!(pmic.rated_coulomb_soc(), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn coulomb_control() {
+        // Paused and clearing
+        let mut pmic = Axp209::new(MockI2c::with(Registers::CoulombBatteryEncryption as u8, 0x60));
+        assert_eq!(pmic.coulomb_control().unwrap(), CoulombControl { enabled: false, paused: true, clearing: true });
+
+        pmic.set_coulomb_control(CoulombControl { enabled: true, paused: false, clearing: false }).unwrap();
+        assert_eq!(register(&pmic, Registers::CoulombBatteryEncryption), 0x80);
+
+        pmic.set_coulomb_control(CoulombControl { enabled: true, paused: true, clearing: true }).unwrap();
+        assert_eq!(register(&pmic, Registers::CoulombBatteryEncryption), 0xe0);
+        assert_eq!(pmic.coulomb_control().unwrap(), CoulombControl { enabled: true, paused: true, clearing: true });
+    }
+
+    #[test]
+    fn coulomb_counter_control() {
+        let mut pmic = Axp209::new(MockI2c::new());
+
+        pmic.coulomb_counter_enable(true).unwrap();
+        assert_eq!(register(&pmic, Registers::CoulombBatteryEncryption), 0x80);
+
+        pmic.coulomb_counter_pause(true).unwrap();
+        assert_eq!(register(&pmic, Registers::CoulombBatteryEncryption), 0xc0);
+
+        pmic.coulomb_counter_pause(false).unwrap();
+        assert_eq!(register(&pmic, Registers::CoulombBatteryEncryption), 0x80);
+
+        pmic.coulomb_counter_reset().unwrap();
+        assert_eq!(register(&pmic, Registers::CoulombBatteryEncryption), 0xa0);
+
+        pmic.device.registers[Registers::CoulombBatteryEncryption as usize] = 0xc0;
+        pmic.coulomb_counter_enable(false).unwrap();
+        assert_eq!(register(&pmic, Registers::CoulombBatteryEncryption), 0x40);
+    }
+
+    #[test]
+    fn battery_present() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::BatteryLevel as u8, 0x7f));
+        assert!(!pmic.battery_present().unwrap());
+
+        // The sampling bit shouldn't matter
+        pmic.device.registers[Registers::BatteryLevel as usize] = 0xff;
+        assert!(!pmic.battery_present().unwrap());
+
+        pmic.device.registers[Registers::BatteryLevel as usize] = 80;
+        assert!(pmic.battery_present().unwrap());
+    }
+
+    #[test]
+    fn soc_from_voltage() {
+        let curve = [(3000, 0), (3600, 50), (4200, 100)];
+        let mut pmic = Axp209::new(MockI2c::new());
+        let mut soc = |raw: [u8; 2], curve: &[(u16, u8)]| {
+            pmic.device.registers[0x78..0x7a].copy_from_slice(&raw);
+            pmic.soc_from_voltage(curve)
+        };
+
+        // 3300mV and 3900mV, halfway between points
+        assert_eq!(soc([0xbb, 0x08], &curve).unwrap(), 25);
+        assert_eq!(soc([0xdd, 0x0a], &curve).unwrap(), 75);
+        // 3168mV
+        assert_eq!(soc([0xb4, 0x00], &curve).unwrap(), 14);
+        // Right on a point
+        assert_eq!(soc([0xcc, 0x09], &curve).unwrap(), 50);
+
+        // 2252mV and 4400mV are off either end
+        assert_eq!(soc([0x80, 0x00], &curve).unwrap(), 0);
+        assert_eq!(soc([0xfa, 0x00], &curve).unwrap(), 100);
+
+        assert_eq!(soc([0xbb, 0x08], &[]), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn battery_net_current() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x7a..0x7e].copy_from_slice(&[
+            0x32, 0x0a, // Charge current, 405mA
+            0x10, 0x04, // Discharge current, 258mA
+        ]);
+        let mut pmic = Axp209::new(mock);
+
+        pmic.device.registers[Registers::PowerStatus as usize] = 0b0101_0100;
+        assert_eq!(pmic.battery_net_current_ma().unwrap(), 405);
+
+        pmic.device.registers[Registers::PowerStatus as usize] = 0b0000_0000;
+        assert_eq!(pmic.battery_net_current_ma().unwrap(), -258);
+    }
+
+    #[test]
+    fn battery_level_opt() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::BatteryLevel as u8, 0x50));
+        assert_eq!(pmic.battery_level_opt().unwrap(), Some(80));
+
+        pmic.device.registers[Registers::BatteryLevel as usize] = 0x80 | BATTERY_LEVEL_MISSING;
+        assert_eq!(pmic.battery_level_opt().unwrap(), None);
+
+        pmic.device.registers[Registers::BatteryLevel as usize] = 0x65;
+        assert_eq!(pmic.battery_level_opt(), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn estimated_runtime() {
+        // 50% of 2000mAh at 258mA
+        let mut pmic = Axp209::new(MockI2c::with(Registers::BatteryLevel as u8, 50));
+        pmic.device.registers[0x7c..0x7e].copy_from_slice(&[0x10, 0x04]);
+        assert_eq!(pmic.estimated_runtime_minutes(2000).unwrap(), 232);
+
+        pmic.device.registers[0x7c..0x7e].copy_from_slice(&[0x00, 0x00]);
+        assert_eq!(pmic.estimated_runtime_minutes(2000).unwrap(), u32::MAX);
+
+        pmic.device.registers[Registers::BatteryLevel as usize] = BATTERY_LEVEL_MISSING;
+        assert_eq!(pmic.estimated_runtime_minutes(2000), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn battery_level_sampling() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::BatteryLevel as u8, 42));
+        assert!(!pmic.battery_level_sampling_enabled().unwrap());
+
+        pmic.set_battery_level_sampling(true).unwrap();
+        assert_eq!(register(&pmic, Registers::BatteryLevel), 0x80 | 42);
+        assert!(pmic.battery_level_sampling_enabled().unwrap());
+        assert_eq!(pmic.battery_level().unwrap(), 42);
+
+        pmic.set_battery_level_sampling(false).unwrap();
+        assert_eq!(register(&pmic, Registers::BatteryLevel), 42);
+    }
+
+    #[test]
+    fn charging_and_discharging() {
+        // Plugged in and charging
+        let mut mock = MockI2c::new();
+        mock.registers[0x00..0x02].copy_from_slice(&[0b0101_0100, 0b0110_0000]);
+        let mut pmic = Axp209::new(mock);
+        assert!(pmic.is_charging().unwrap());
+        assert!(!pmic.is_discharging().unwrap());
+        assert_eq!(pmic.is_charging().unwrap(), pmic.charging_status().unwrap().charging());
+
+        // Running off the battery
+        pmic.device.registers[0x00..0x02].copy_from_slice(&[0b0000_0000, 0b0010_0000]);
+        assert!(!pmic.is_charging().unwrap());
+        assert!(pmic.is_discharging().unwrap());
+
+        // Plugged in with a full battery
+        pmic.device.registers[0x00..0x02].copy_from_slice(&[0b0101_0000, 0b0010_0000]);
+        assert!(!pmic.is_charging().unwrap());
+        assert!(!pmic.is_discharging().unwrap());
+
+        // No battery at all
+        pmic.device.registers[0x00..0x02].copy_from_slice(&[0b0000_0000, 0b0000_0000]);
+        assert!(!pmic.is_charging().unwrap());
+        assert!(!pmic.is_discharging().unwrap());
+    }
+
+    #[test]
+    fn power_mode() {
+        let mut pmic = Axp209::new(MockI2c::new());
+        let mut mode = |registers: [u8; 2]| {
+            pmic.device.registers[0x00..0x02].copy_from_slice(&registers);
+            pmic.power_mode().unwrap()
+        };
+
+        // On battery
+        assert_eq!(mode([0b0000_0000, 0b0010_0000]), PowerMode::Active);
+        // Plugged in but not usable
+        assert_eq!(mode([0b1010_0000, 0b0010_0000]), PowerMode::Active);
+        // VBUS in and charging
+        assert_eq!(mode([0b0011_0100, 0b0110_0000]), PowerMode::ChargingOnly);
+        // ACIN in with a full battery
+        assert_eq!(mode([0b1100_0000, 0b0010_0000]), PowerMode::Standby);
+        // ACIN in without a battery
+        assert_eq!(mode([0b1100_0000, 0b0000_0000]), PowerMode::Standby);
+    }
+
+    #[test]
+    fn power_status_decoding() {
+        // USB plugged in and charging the battery
+        let mut pmic = Axp209::new(MockI2c::with(Registers::PowerStatus as u8, 0b0011_1100));
+
+        let status = pmic.power_status().unwrap();
+        assert!(!status.acin_present());
+        assert!(!status.acin_valid());
+        assert!(status.vbus_present());
+        assert!(status.vbus_valid());
+        assert!(status.charging());
+        assert!(status.powered_by_vbus());
+
+        // Both inputs usable, battery draining
+        pmic.device.registers[Registers::PowerStatus as usize] = 0b1111_0000;
+
+        let status = pmic.power_status().unwrap();
+        assert!(status.acin_valid());
+        assert!(!status.charging());
+        assert!(!status.powered_by_vbus());
+    }
+
+    #[test]
+    fn vbus_session_status_decoding() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::VbusSessionStatus as u8, 0b0000_0110));
+        let status = pmic.vbus_session_status().unwrap();
+        assert!(status.vbus_valid());
+        assert!(status.session_valid());
+        assert!(!status.session_ended());
+
+        pmic.device.registers[Registers::VbusSessionStatus as usize] = 0b1111_1001;
+        let status = pmic.vbus_session_status().unwrap();
+        assert!(!status.vbus_valid());
+        assert!(!status.session_valid());
+        assert!(status.session_ended());
+    }
+
+    #[test]
+    fn charging_status_decoding() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ChargingStatus as u8, 0b0110_0000));
+
+        let status = pmic.charging_status().unwrap();
+        assert!(status.charging());
+        assert!(status.battery_present());
+        assert!(!status.over_temperature());
+        assert!(!status.battery_active());
+
+        pmic.device.registers[Registers::ChargingStatus as usize] = 0b1000_0000;
+
+        let status = pmic.charging_status().unwrap();
+        assert!(status.over_temperature());
+        assert!(!status.charging());
+        assert!(!status.battery_present());
+    }
+
+    #[test]
+    fn charge_target_voltage() {
+        // Charging enabled at 4.1V, 1000mA
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ChargeControl as u8, 0b1000_0111));
+
+        assert_eq!(pmic.charge_target_voltage().unwrap(), ChargeTargetVoltage::V4_10);
+
+        pmic.set_charge_target_voltage(ChargeTargetVoltage::V4_20).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeControl), 0b1100_0111);
+        assert_eq!(pmic.charge_target_voltage().unwrap(), ChargeTargetVoltage::V4_20);
+
+        pmic.set_charge_target_voltage(ChargeTargetVoltage::V4_36).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeControl), 0b1110_0111);
+    }
+
+    #[test]
+    fn charge_current() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ChargeControl as u8, 0b1100_1000));
+
+        pmic.set_charge_current_ma(300).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeControl), 0b1100_0000);
+        assert_eq!(pmic.charge_current_ma().unwrap(), 300);
+
+        pmic.set_charge_current_ma(1800).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeControl), 0b1100_1111);
+        assert_eq!(pmic.charge_current_ma().unwrap(), 1800);
+
+        pmic.set_charge_current_ma(2000).unwrap();
+        assert_eq!(pmic.charge_current_ma().unwrap(), 1800);
+
+        pmic.set_charge_current_ma(640).unwrap();
+        assert_eq!(pmic.charge_current_ma().unwrap(), 600);
+    }
+
+    #[test]
+    fn charging_enable_preserves_settings() {
+        // 4.2V at 1000mA
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ChargeControl as u8, 0b1100_0111));
+        assert!(pmic.charging_enabled().unwrap());
+
+        pmic.set_charging_enabled(false).unwrap();
+        assert!(!pmic.charging_enabled().unwrap());
+        assert_eq!(register(&pmic, Registers::ChargeControl), 0b0100_0111);
+        assert_eq!(pmic.charge_target_voltage().unwrap(), ChargeTargetVoltage::V4_20);
+        assert_eq!(pmic.charge_current_ma().unwrap(), 1000);
+
+        pmic.set_charging_enabled(true).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeControl), 0b1100_0111);
+    }
+
+    #[test]
+    fn vbus_current_limit() {
+        // VHOLD limited to 4.4V, 500mA limit
+        let mut pmic = Axp209::new(MockI2c::with(Registers::VbusControl as u8, 0x61));
+        assert_eq!(pmic.vbus_current_limit().unwrap(), VbusCurrentLimit::Ma500);
+
+        for &(limit, code) in &[
+            (VbusCurrentLimit::Ma900, 0b00),
+            (VbusCurrentLimit::Ma500, 0b01),
+            (VbusCurrentLimit::Ma100, 0b10),
+            (VbusCurrentLimit::Unlimited, 0b11),
+        ] {
+            pmic.set_vbus_current_limit(limit).unwrap();
+            assert_eq!(register(&pmic, Registers::VbusControl), 0x60 | code);
+            assert_eq!(pmic.vbus_current_limit().unwrap(), limit);
+        }
+    }
+
+    #[test]
+    fn vbus_pin_control() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::VbusControl as u8, 0x61));
+        assert!(pmic.vbus_pin_control().unwrap());
+
+        pmic.set_vbus_pin_control(false).unwrap();
+        assert_eq!(register(&pmic, Registers::VbusControl), 0xe1);
+        assert!(!pmic.vbus_pin_control().unwrap());
+        assert_eq!(pmic.vbus_current_limit().unwrap(), VbusCurrentLimit::Ma500);
+
+        pmic.set_vbus_pin_control(true).unwrap();
+        assert_eq!(register(&pmic, Registers::VbusControl), 0x61);
+    }
+
+    #[test]
+    fn sleep_mode_and_pwrok_delay() {
+        let mut mock = MockI2c::new();
+        // VOFF at 2.9V, PEK at 3s on, 1.5s long press, 6s off
+        mock.registers[Registers::PowerOffVoltage as usize] = 0x03;
+        mock.registers[Registers::PekConfig as usize] = 0x59;
+        let mut pmic = Axp209::new(mock);
+
+        assert!(!pmic.sleep_mode().unwrap());
+        pmic.set_sleep_mode(true).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerOffVoltage), 0x0b);
+        assert!(pmic.sleep_mode().unwrap());
+        assert_eq!(pmic.voff_voltage_mv().unwrap(), 2900);
+        pmic.set_sleep_mode(false).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerOffVoltage), 0x03);
+
+        assert_eq!(pmic.pwrok_delay().unwrap(), PwrokDelay::Ms8);
+        pmic.set_pwrok_delay(PwrokDelay::Ms64).unwrap();
+        assert_eq!(register(&pmic, Registers::PekConfig), 0x5d);
+        assert_eq!(pmic.pwrok_delay().unwrap(), PwrokDelay::Ms64);
+        // Neither touched the other's register
+        assert_eq!(register(&pmic, Registers::PowerOffVoltage), 0x03);
+    }
+
+    #[test]
+    fn voff_voltage() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::PowerOffVoltage as u8, 0x08));
+
+        pmic.set_voff_voltage_mv(2600).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerOffVoltage), 0x08);
+        assert_eq!(pmic.voff_voltage_mv().unwrap(), 2600);
+
+        pmic.set_voff_voltage_mv(3300).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerOffVoltage), 0x0f);
+        assert_eq!(pmic.voff_voltage_mv().unwrap(), 3300);
+
+        pmic.set_voff_voltage_mv(3000).unwrap();
+        assert_eq!(register(&pmic, Registers::PowerOffVoltage), 0x0c);
+        assert_eq!(pmic.voff_voltage_mv().unwrap(), 3000);
+    }
+
+    #[test]
+    fn read_registers() {
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x48], vec![0x01, 0x02, 0x03, 0x04, 0x05]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        let registers: [u8; 5] = pmic.read_registers(0x48).unwrap();
+        assert_eq!(registers, [0x01, 0x02, 0x03, 0x04, 0x05]);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn modify_register() {
+        let mut pmic = Axp209::new(MockI2c::with(0x0c, 0b1010_0101));
+
+        pmic.modify_register(0x0c, |value| value ^ 0b0000_0100).unwrap();
+        assert_eq!(pmic.read_register(0x0c).unwrap(), 0b1010_0001);
+
+        pmic.write_register(0x0c, 0x42).unwrap();
+        assert_eq!(pmic.read_register(0x0c).unwrap(), 0x42);
+    }
+
+    #[test]
+    fn register_addresses() {
+        assert_eq!(u8::from(Registers::PowerStatus), 0x00);
+        assert_eq!(u8::from(Registers::AdcControl), 0x82);
+        assert_eq!(u8::from(Registers::BatteryLevel), 0xb9);
+
+        let mut pmic = Axp209::new(MockI2c::with(Registers::PowerStatus as u8, 0x30));
+        assert_eq!(pmic.read_register(Registers::PowerStatus.into()).unwrap(), 0x30);
+    }
+
+    #[test]
+    fn impossible_battery_level() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::BatteryLevel as u8, 0x80 | 100));
+        assert_eq!(pmic.battery_level(), Ok(100));
+
+        pmic.device.registers[Registers::BatteryLevel as usize] = 101;
+        assert_eq!(pmic.battery_level(), Err(Error::InvalidData));
+        assert_eq!(pmic.battery_present(), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn free_returns_the_bus() {
+        let mut pmic = Axp209::new(MockI2c::with(0x06, 0x42));
+        pmic.write_register(0x07, 0x24).unwrap();
+
+        let bus = pmic.free();
+        assert_eq!(bus.registers[0x06], 0x42);
+        assert_eq!(bus.registers[0x07], 0x24);
+    }
+
+    #[test]
+    fn new_default_uses_0x34() {
+        assert_eq!(AXP209_DEFAULT_ADDRESS, 0x34);
+
+        let transactions = [Transaction::write_read(0x34, vec![0xb9], vec![87])];
+        let mut pmic = Axp209::new_default(I2cMock::new(&transactions));
+        assert_eq!(pmic.battery_level().unwrap(), 87);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn detect_variant() {
+        // The top nibble isn't part of the version
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ChipVersion as u8, 0x51));
+        assert_eq!(pmic.detect_variant().unwrap(), ChipVariant::Axp20x);
+
+        pmic.device.registers[Registers::ChipVersion as usize] = 0x03;
+        assert_eq!(pmic.detect_variant().unwrap(), ChipVariant::Unknown(0x3));
+    }
+
+    #[test]
+    fn try_new() {
+        let transactions = [Transaction::write_read(0x34, vec![0x00], vec![0x00])];
+        let pmic = Axp209::try_new(I2cMock::new(&transactions)).unwrap();
+        pmic.free().done();
+
+        // Nobody home
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let transactions = [Transaction::write_read(0x34, vec![0x00], vec![0x00]).with_error(nack)];
+        let mut mock = I2cMock::new(&transactions);
+        assert!(match Axp209::try_new(mock.clone()) {
+            Err(Error::I2c(error)) => error == nack,
+            _ => false,
+        });
+        mock.done();
+    }
+
+    #[cfg(feature = "nb")]
+    #[test]
+    fn nb_would_block() {
+        let busy = ErrorKind::ArbitrationLoss;
+        let nack = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+        let transactions = [
+            Transaction::write_read(0x34, vec![0x78], vec![0x00, 0x00]).with_error(busy),
+            Transaction::write_read(0x34, vec![0x78], vec![0xb4, 0x00]),
+            Transaction::write_read(0x34, vec![0x78], vec![0x00, 0x00]).with_error(nack),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        assert_eq!(pmic.try_battery_voltage(), Err(nb::Error::WouldBlock));
+        assert_eq!(nb::block!(pmic.try_battery_voltage()).unwrap(), 3168);
+        assert_eq!(pmic.try_battery_voltage(), Err(nb::Error::Other(Error::I2c(nack))));
+        pmic.free().done();
+    }
+
+    #[test]
+    fn adc_enable_predicates() {
+        let mut mock = MockI2c::new();
+        // Battery current, ACIN voltage, VBUS current, TS and GPIO1
+        mock.registers[0x82..0x84].copy_from_slice(&[0b0110_0101, 0b0000_0100]);
+        let mut pmic = Axp209::new(mock);
+
+        let adc = pmic.adc_control().unwrap();
+        assert!(!adc.battery_voltage());
+        assert!(adc.battery_current());
+        assert!(adc.acin_voltage());
+        assert!(!adc.acin_current());
+        assert!(!adc.vbus_voltage());
+        assert!(adc.vbus_current());
+        assert!(!adc.aps_voltage());
+        assert!(adc.ts_function());
+        assert!(!adc.temperature());
+        assert!(!adc.gpio0());
+        assert!(adc.gpio1());
+
+        assert!(pmic.adc_enabled(AdcControl::BATTERY_CURRENT | AdcControl::GPIO1).unwrap());
+        assert!(!pmic.adc_enabled(AdcControl::BATTERY_CURRENT | AdcControl::BATTERY_VOLTAGE).unwrap());
+    }
+
+    #[test]
+    fn adc_control_round_trip() {
+        // Battery voltage, APS, TS and the internal temperature
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x82], vec![0x83, 0x80]),
+            Transaction::write(ADDRESS, vec![0x82, 0xc3]),
+            Transaction::write(ADDRESS, vec![0x83, 0x80]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        let mut adc = pmic.adc_control().unwrap();
+        adc.set_battery_current(true);
+        pmic.set_adc_control(adc).unwrap();
+        pmic.free().done();
+    }
+
+    #[test]
+    fn adc_control() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x82..0x84].copy_from_slice(&[0x83, 0x80]);
+        let mut pmic = Axp209::new(mock);
+
+        pmic.set_adc_control(AdcControl::BATTERY_VOLTAGE).unwrap();
+        assert_eq!(&pmic.device.registers[0x82..0x84], &[0x80, 0x00]);
+        assert!(pmic.adc_control().unwrap().battery_voltage());
+        assert!(!pmic.adc_control().unwrap().temperature());
+
+        pmic.enable_all_adc().unwrap();
+        assert_eq!(&pmic.device.registers[0x82..0x84], &[0xff, 0x8c]);
+    }
+
+    #[test]
+    fn adc_sample_rate() {
+        // TS pin current and function bits set
+        let mut pmic = Axp209::new(MockI2c::with(Registers::AdcSpeed as u8, 0x33));
+        assert_eq!(pmic.adc_sample_rate().unwrap(), AdcSampleRate::Hz25);
+
+        for &(rate, code, hz) in &[
+            (AdcSampleRate::Hz25, 0b00, 25),
+            (AdcSampleRate::Hz50, 0b01, 50),
+            (AdcSampleRate::Hz100, 0b10, 100),
+            (AdcSampleRate::Hz200, 0b11, 200),
+        ] {
+            pmic.set_adc_sample_rate(rate).unwrap();
+            assert_eq!(register(&pmic, Registers::AdcSpeed), (code << 6) | 0x33);
+            assert_eq!(pmic.adc_sample_rate().unwrap(), rate);
+            assert_eq!(rate.hz(), hz);
+        }
+    }
+
+    #[test]
+    fn pek_config() {
+        // 3s on, 1.5s long press, 6s off and the 64ms PWROK delay
+        let mut pmic = Axp209::new(MockI2c::with(Registers::PekConfig as u8, 0x5d));
+        assert_eq!(pmic.pek_config().unwrap(), PekConfig {
+            power_on_time: PekPowerOnTime::S3,
+            long_press_time: PekLongPressTime::S1_5,
+            power_off_enabled: true,
+            power_off_time: PekPowerOffTime::S6,
+        });
+
+        let config = PekConfig {
+            power_on_time: PekPowerOnTime::S1,
+            long_press_time: PekLongPressTime::S2,
+            power_off_enabled: true,
+            power_off_time: PekPowerOffTime::S6,
+        };
+        assert_eq!(config.to_register(), 0b1010_1001);
+
+        pmic.set_pek_config(config).unwrap();
+        assert_eq!(register(&pmic, Registers::PekConfig), 0b1010_1101);
+        assert_eq!(pmic.pek_config().unwrap(), config);
+    }
+
+    #[test]
+    fn chgled_mode() {
+        // Battery detection on, N_OE delay of 2s
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ShutdownControl as u8, 0x42));
+        assert_eq!(pmic.chgled_mode().unwrap(), ChgLedMode::HighImpedance);
+
+        pmic.set_chgled_source(true).unwrap();
+        assert_eq!(register(&pmic, Registers::ShutdownControl), 0x4a);
+
+        for &(mode, code) in &[
+            (ChgLedMode::HighImpedance, 0b00),
+            (ChgLedMode::Flash1Hz, 0b01),
+            (ChgLedMode::Flash4Hz, 0b10),
+            (ChgLedMode::OutputLow, 0b11),
+        ] {
+            pmic.set_chgled_mode(mode).unwrap();
+            assert_eq!(register(&pmic, Registers::ShutdownControl), 0x4a | (code << 4));
+            assert_eq!(pmic.chgled_mode().unwrap(), mode);
+        }
+
+        pmic.set_chgled_source(false).unwrap();
+        assert_eq!(register(&pmic, Registers::ShutdownControl), 0x72);
+    }
+
+    #[test]
+    fn noe_shutdown_delay() {
+        // Battery detection on, N_OE delay of 2s
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ShutdownControl as u8, 0x42));
+        assert_eq!(pmic.noe_shutdown_delay().unwrap(), NoeDelay::S2);
+
+        for &(delay, code) in &[
+            (NoeDelay::Ms128, 0b00),
+            (NoeDelay::S1, 0b01),
+            (NoeDelay::S2, 0b10),
+            (NoeDelay::S3, 0b11),
+        ] {
+            pmic.set_noe_shutdown_delay(delay).unwrap();
+            assert_eq!(register(&pmic, Registers::ShutdownControl), 0x40 | code);
+            assert_eq!(pmic.noe_shutdown_delay().unwrap(), delay);
+        }
+    }
+
+    #[test]
+    fn dcdc_frequency() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::DcdcFrequency as u8, 0x08));
+        assert_eq!(pmic.dcdc_frequency().unwrap(), 0);
+
+        pmic.set_dcdc_frequency(3).unwrap();
+        assert_eq!(register(&pmic, Registers::DcdcFrequency), 0x0b);
+        assert_eq!(pmic.dcdc_frequency().unwrap(), 3);
+
+        pmic.set_dcdc_frequency(-5).unwrap();
+        assert_eq!(register(&pmic, Registers::DcdcFrequency), 0x03);
+        assert_eq!(pmic.dcdc_frequency().unwrap(), -5);
+
+        // Out of range in either direction
+        pmic.set_dcdc_frequency(20).unwrap();
+        assert_eq!(pmic.dcdc_frequency().unwrap(), 7);
+        pmic.set_dcdc_frequency(-20).unwrap();
+        assert_eq!(register(&pmic, Registers::DcdcFrequency), 0x00);
+    }
+
+    #[test]
+    fn overtemperature_shutdown() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::OverTemperature as u8, 0x80));
+        assert!(!pmic.overtemperature_shutdown().unwrap());
+
+        pmic.set_overtemperature_shutdown(true).unwrap();
+        assert_eq!(register(&pmic, Registers::OverTemperature), 0x84);
+        assert!(pmic.overtemperature_shutdown().unwrap());
+
+        pmic.set_overtemperature_shutdown(false).unwrap();
+        assert_eq!(register(&pmic, Registers::OverTemperature), 0x80);
+    }
+
+    #[test]
+    fn charge_timeouts() {
+        // The datasheet's default, 50 minutes and 8 hours
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ChargeControl2 as u8, 0x41));
+        assert_eq!(pmic.charge_timeouts().unwrap(), ChargeTimeouts {
+            precharge: PrechargeTimeout::Min50,
+            constant_current: ChargeTimeout::Hours8,
+        });
+
+        let timeouts = ChargeTimeouts {
+            precharge: PrechargeTimeout::Min70,
+            constant_current: ChargeTimeout::Hours10,
+        };
+        pmic.device.registers[Registers::ChargeControl2 as usize] = 0x10;
+        pmic.set_charge_timeouts(timeouts).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeControl2), 0b1101_0011);
+        assert_eq!(pmic.charge_timeouts().unwrap(), timeouts);
+
+        let timeouts = ChargeTimeouts {
+            precharge: PrechargeTimeout::Min60,
+            constant_current: ChargeTimeout::Hours7,
+        };
+        assert_eq!(timeouts.to_register(), 0b1000_0000);
+    }
+
+    #[test]
+    fn backup_battery_config() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::BackupBatteryControl as u8, 0x22));
+        assert_eq!(pmic.backup_battery_config().unwrap(), BackupBatteryConfig {
+            enabled: false,
+            voltage: BackupBatteryVoltage::V3_0,
+            current: BackupBatteryCurrent::Ua200,
+        });
+
+        let config = BackupBatteryConfig {
+            enabled: true,
+            voltage: BackupBatteryVoltage::V3_0,
+            current: BackupBatteryCurrent::Ua200,
+        };
+        pmic.set_backup_battery_charging(config).unwrap();
+        assert_eq!(register(&pmic, Registers::BackupBatteryControl), 0b1010_0010);
+        assert_eq!(pmic.backup_battery_config().unwrap(), config);
+    }
+
+    #[test]
+    fn gpio_mode() {
+        let mut mock = MockI2c::new();
+        mock.registers[Registers::Gpio0Control as usize] = 0x04;
+        mock.registers[Registers::Gpio1Control as usize] = 0x02;
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(pmic.gpio_mode(Gpio::Gpio0).unwrap(), GpioMode::Adc);
+        assert_eq!(pmic.gpio_mode(Gpio::Gpio1).unwrap(), GpioMode::Input);
+
+        pmic.set_gpio_mode(Gpio::Gpio1, GpioMode::OutputLow).unwrap();
+        assert_eq!(register(&pmic, Registers::Gpio1Control), 0x00);
+        assert_eq!(register(&pmic, Registers::Gpio0Control), 0x04);
+        assert_eq!(pmic.gpio_mode(Gpio::Gpio1).unwrap(), GpioMode::OutputLow);
+
+        pmic.set_gpio_mode(Gpio::Gpio3, GpioMode::Input).unwrap();
+        assert_eq!(register(&pmic, Registers::Gpio3Control), 0x04);
+        assert_eq!(pmic.gpio_mode(Gpio::Gpio3).unwrap(), GpioMode::Input);
+
+        assert_eq!(pmic.set_gpio_mode(Gpio::Gpio2, GpioMode::Adc), Err(Error::InvalidData));
+        assert_eq!(register(&pmic, Registers::Gpio2Control), 0x00);
+    }
+
+    #[test]
+    fn gpio_read_and_write() {
+        // GPIO1 is high, GPIO3 is low
+        let mut pmic = Axp209::new(MockI2c::with(Registers::GpioState as u8, 0x20));
+        pmic.device.registers[Registers::Gpio3Control as usize] = 0x04;
+
+        assert!(!pmic.gpio_read(Gpio::Gpio0).unwrap());
+        assert!(pmic.gpio_read(Gpio::Gpio1).unwrap());
+        assert!(!pmic.gpio_read(Gpio::Gpio3).unwrap());
+
+        pmic.device.registers[Registers::Gpio3Control as usize] = 0x05;
+        assert!(pmic.gpio_read(Gpio::Gpio3).unwrap());
+
+        pmic.device.registers[Registers::Gpio0Control as usize] = 0x02;
+        pmic.device.registers[Registers::Gpio1Control as usize] = 0x02;
+        pmic.gpio_write(Gpio::Gpio0, true).unwrap();
+        assert_eq!(register(&pmic, Registers::Gpio0Control), 0x01);
+        assert_eq!(register(&pmic, Registers::Gpio1Control), 0x02);
+
+        pmic.gpio_write(Gpio::Gpio2, true).unwrap();
+        assert_eq!(register(&pmic, Registers::Gpio2Control), 0x01);
+
+        pmic.gpio_write(Gpio::Gpio0, false).unwrap();
+        assert_eq!(register(&pmic, Registers::Gpio0Control), 0x00);
+    }
+
+    #[test]
+    fn gpio_voltage_ranges() {
+        let mut mock = MockI2c::new();
+        // 0xfff is the top of either range
+        mock.registers[0x64..0x68].copy_from_slice(&[0xff, 0x0f, 0xff, 0x0f]);
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(pmic.gpio0_voltage().unwrap(), 2047);
+        assert_eq!(pmic.gpio1_voltage().unwrap(), 2047);
+
+        // Only GPIO1 on the upper range
+        pmic.device.registers[Registers::GpioAdcRange as usize] = 0b10;
+        assert_eq!(pmic.gpio0_voltage().unwrap(), 2047);
+        assert_eq!(pmic.gpio1_voltage().unwrap(), 2747);
+
+        pmic.device.registers[0x64..0x66].copy_from_slice(&[0x00, 0x00]);
+        pmic.device.registers[Registers::GpioAdcRange as usize] = 0b01;
+        assert_eq!(pmic.gpio0_voltage().unwrap(), 700);
+    }
+
+    #[test]
+    fn gpio_adc_range_switching() {
+        let mut mock = MockI2c::new();
+        // Other bits of 0x85 are left as they were
+        mock.registers[Registers::GpioAdcRange as usize] = 0xf0;
+        mock.registers[0x64..0x68].copy_from_slice(&[0x3e, 0x08, 0x3e, 0x08]);
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(pmic.gpio_adc_range(Gpio::Gpio0).unwrap(), GpioAdcRange::Low);
+        assert_eq!(pmic.gpio0_voltage().unwrap(), 500);
+
+        pmic.set_gpio_adc_range(Gpio::Gpio0, GpioAdcRange::High).unwrap();
+        assert_eq!(register(&pmic, Registers::GpioAdcRange), 0xf1);
+        assert_eq!(pmic.gpio_adc_range(Gpio::Gpio0).unwrap(), GpioAdcRange::High);
+        assert_eq!(pmic.gpio0_voltage().unwrap(), 1200);
+        assert_eq!(pmic.gpio1_voltage().unwrap(), 500);
+
+        pmic.set_gpio_adc_range(Gpio::Gpio1, GpioAdcRange::High).unwrap();
+        pmic.set_gpio_adc_range(Gpio::Gpio0, GpioAdcRange::Low).unwrap();
+        assert_eq!(register(&pmic, Registers::GpioAdcRange), 0xf2);
+        assert_eq!(pmic.gpio1_voltage().unwrap(), 1200);
+
+        assert_eq!(pmic.set_gpio_adc_range(Gpio::Gpio2, GpioAdcRange::High), Err(Error::InvalidData));
+        assert_eq!(pmic.gpio_adc_range(Gpio::Gpio3), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn data_buffer() {
+        let mut pmic = Axp209::new(MockI2c::new());
+
+        pmic.write_data_buffer(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(pmic.read_data_buffer().unwrap(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(&pmic.device.registers[0x06..0x0c], &[1, 2, 3, 4, 5, 6]);
+
+        pmic.set_data_buffer_byte(5, 0x42).unwrap();
+        assert_eq!(pmic.data_buffer_byte(5).unwrap(), 0x42);
+        assert_eq!(pmic.data_buffer_byte(0).unwrap(), 1);
+        assert_eq!(pmic.read_data_buffer().unwrap(), [1, 2, 3, 4, 5, 0x42]);
+
+        assert_eq!(pmic.data_buffer_byte(6), Err(Error::InvalidData));
+        assert_eq!(pmic.set_data_buffer_byte(6, 0), Err(Error::InvalidData));
+        assert_eq!(pmic.device.registers[0x0c], 0);
+    }
+
+    #[test]
+    fn probe_restores_the_data_buffer() {
+        let mut pmic = Axp209::new(MockI2c::with(Registers::DataBuffer as u8 + 5, 0x42));
+
+        assert!(pmic.probe().unwrap());
+        assert_eq!(&pmic.device.registers[0x06..0x0c], &[0, 0, 0, 0, 0, 0x42]);
+    }
+
+    #[test]
+    fn probe_spots_a_missing_chip() {
+        // Something that ignores writes and reads back all 1s, like a bus
+        // with nothing pulling the lines low
+        let pattern = [0x55, 0xaa, 0x0f, 0xf0, 0x3c, 0xc3];
+        let mut transactions = vec![Transaction::write_read(ADDRESS, vec![0x06], vec![0xff; 6])];
+        for (i, value) in pattern.iter().enumerate() {
+            transactions.push(Transaction::write(ADDRESS, vec![0x06 + i as u8, *value]));
+        }
+        transactions.push(Transaction::write_read(ADDRESS, vec![0x06], vec![0xff; 6]));
+        for i in 0..6 {
+            transactions.push(Transaction::write(ADDRESS, vec![0x06 + i, 0xff]));
+        }
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        assert!(!pmic.probe().unwrap());
+        pmic.free().done();
+    }
+
+    #[test]
+    fn ts_thresholds() {
+        let mut pmic = Axp209::new(MockI2c::new());
+
+        // The datasheet's defaults, 2.112V and 0.397V
+        pmic.set_charge_temp_low_mv(2112).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeTempLow), 0xa5);
+        assert_eq!(pmic.charge_temp_low_mv().unwrap(), 2112);
+
+        pmic.set_charge_temp_high_mv(397).unwrap();
+        assert_eq!(register(&pmic, Registers::ChargeTempHigh), 0x1f);
+        assert_eq!(pmic.charge_temp_high_mv().unwrap(), 396);
+
+        // 3.226V and 0.282V
+        pmic.set_discharge_temp_low_mv(3226).unwrap();
+        assert_eq!(register(&pmic, Registers::DischargeTempLow), 0xfc);
+        pmic.set_discharge_temp_high_mv(282).unwrap();
+        assert_eq!(register(&pmic, Registers::DischargeTempHigh), 0x16);
+
+        // Too high for the register
+        pmic.set_discharge_temp_low_mv(5000).unwrap();
+        assert_eq!(pmic.discharge_temp_low_mv().unwrap(), 3264);
+    }
+
+    #[test]
+    fn aps_warning_levels() {
+        // The datasheet's defaults, 0x68 and 0x5f
+        let mut pmic = Axp209::new(MockI2c::with(Registers::ApsWarningLevel1 as u8, 0x68));
+        pmic.device.registers[Registers::ApsWarningLevel2 as usize] = 0x5f;
+        assert_eq!(pmic.aps_warning_level1_mv().unwrap(), 3449);
+        assert_eq!(pmic.aps_warning_level2_mv().unwrap(), 3399);
+
+        pmic.set_aps_warning_level1_mv(3450).unwrap();
+        assert_eq!(register(&pmic, Registers::ApsWarningLevel1), 0x68);
+        pmic.set_aps_warning_level2_mv(3399).unwrap();
+        assert_eq!(register(&pmic, Registers::ApsWarningLevel2), 0x5f);
+
+        // Either end of the range
+        pmic.set_aps_warning_level1_mv(2000).unwrap();
+        assert_eq!(pmic.aps_warning_level1_mv().unwrap(), 2867);
+        pmic.set_aps_warning_level1_mv(5000).unwrap();
+        assert_eq!(pmic.aps_warning_level1_mv().unwrap(), 4295);
+    }
+
+    #[test]
+    fn shared_bus() {
+        use core::cell::RefCell;
+        use embedded_hal_bus::i2c::RefCellDevice;
+
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0xb9], vec![87]),
+            Transaction::write(0x68, vec![0x00, 0x12]),
+            Transaction::write_read(ADDRESS, vec![0xb9], vec![86]),
+        ];
+        let bus = RefCell::new(I2cMock::new(&transactions));
+
+        // Something else on the bus, like an RTC
+        let mut other = RefCellDevice::new(&bus);
+        let mut pmic = Axp209::new(RefCellDevice::new(&bus));
+
+        assert_eq!(pmic.battery_level().unwrap(), 87);
+        other.write(0x68, &[0x00, 0x12]).unwrap();
+        assert_eq!(pmic.battery_level().unwrap(), 86);
+
+        pmic.free();
+        bus.into_inner().done();
+    }
+
+    #[test]
+    fn dcdc2_vrc() {
+        // LDO3's ramp enable and slope are set
+        let mut pmic = Axp209::new(MockI2c::with(Registers::VoltageRamp as u8, 0b1010));
+        assert_eq!(pmic.dcdc2_vrc().unwrap(), Dcdc2Vrc { enabled: false, slope: VrcSlope::Us15_625 });
+
+        pmic.set_dcdc2_vrc(Dcdc2Vrc { enabled: true, slope: VrcSlope::Us31_25 }).unwrap();
+        assert_eq!(register(&pmic, Registers::VoltageRamp), 0b1111);
+        assert_eq!(pmic.dcdc2_vrc().unwrap(), Dcdc2Vrc { enabled: true, slope: VrcSlope::Us31_25 });
+
+        pmic.set_dcdc2_vrc(Dcdc2Vrc { enabled: true, slope: VrcSlope::Us15_625 }).unwrap();
+        assert_eq!(register(&pmic, Registers::VoltageRamp), 0b1110);
+    }
+
+    #[test]
+    fn checked_rail_voltages() {
+        let mut pmic = Axp209::new(MockI2c::new());
+        pmic.set_dcdc2_voltage_mv_checked(1400).unwrap();
+        pmic.set_dcdc3_voltage_mv_checked(3600).unwrap();
+        pmic.set_ldo2_voltage_mv_checked(3000).unwrap();
+        pmic.set_ldo3_voltage_mv_checked(2800).unwrap();
+        assert_eq!(pmic.dcdc3_voltage_mv().unwrap(), 3500);
+
+        // The chip hangs on to the old 1250mV
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x27], vec![0x16]),
+            Transaction::write(ADDRESS, vec![0x27, 0x1c]),
+            Transaction::write_read(ADDRESS, vec![0x27], vec![0x16]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        assert_eq!(pmic.set_dcdc3_voltage_mv_checked(1400), Err(Error::InvalidData));
+        pmic.free().done();
+    }
+
+    #[test]
+    fn ramp_dcdc2_voltage() {
+        // 900mV up to 1200mV
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x23], vec![0x08]),
+            Transaction::write(ADDRESS, vec![0x23, 0x0c]),
+            Transaction::write(ADDRESS, vec![0x23, 0x10]),
+            Transaction::write(ADDRESS, vec![0x23, 0x14]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        pmic.ramp_dcdc2_voltage_mv(1200, 100).unwrap();
+        pmic.free().done();
+
+        // And down from 1200mV to 1050mV, the last step only going halfway
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x23], vec![0x14]),
+            Transaction::write(ADDRESS, vec![0x23, 0x10]),
+            Transaction::write(ADDRESS, vec![0x23, 0x0e]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        pmic.ramp_dcdc2_voltage_mv(1050, 100).unwrap();
+        pmic.free().done();
+
+        let mut pmic = Axp209::new(MockI2c::new());
+        assert_eq!(pmic.ramp_dcdc2_voltage_mv(1200, 0), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn dcdc3_voltage_clamps_to_max_step() {
+        let mut pmic = Axp209::new(MockI2c::new());
+
+        pmic.set_dcdc3_voltage_mv(3500).unwrap();
+        assert_eq!(register(&pmic, Registers::Dcdc3Voltage), 112);
+
+        pmic.set_dcdc3_voltage_mv(3510).unwrap();
+        assert_eq!(register(&pmic, Registers::Dcdc3Voltage), 112);
+        assert_eq!(pmic.dcdc3_voltage_mv().unwrap(), 3500);
+
+        pmic.set_dcdc3_voltage_mv(1200).unwrap();
+        assert_eq!(register(&pmic, Registers::Dcdc3Voltage), 20);
+    }
+
+    #[test]
+    fn ldo2_and_ldo3_voltages() {
+        // LDO4 is sitting in the low nibble, LDO3 is in tracking mode
+        let mut pmic = Axp209::new(MockI2c::with(Registers::Ldo24Voltage as u8, 0x09));
+        pmic.device.registers[Registers::Ldo3Voltage as usize] = 0x80;
+
+        pmic.set_ldo2_voltage_mv(3000).unwrap();
+        pmic.set_ldo3_voltage_mv(3300).unwrap();
+
+        assert_eq!(register(&pmic, Registers::Ldo24Voltage), 0xc9);
+        assert_eq!(register(&pmic, Registers::Ldo3Voltage), 0x80 | 104);
+        assert_eq!(pmic.ldo2_voltage_mv().unwrap(), 3000);
+        assert_eq!(pmic.ldo3_voltage_mv().unwrap(), 3300);
+
+        pmic.set_ldo2_voltage_mv(1849).unwrap();
+        assert_eq!(pmic.ldo2_voltage_mv().unwrap(), 1800);
+        assert_eq!(register(&pmic, Registers::Ldo24Voltage), 0x09);
+    }
+
+    #[test]
+    fn measurements() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x56..0x60].copy_from_slice(&[
+            0xb0, 0x00, // ACIN voltage
+            0x40, 0x00, // ACIN current
+            0xb0, 0x00, // VBUS voltage
+            0x10, 0x00, // VBUS current
+            0x69, 0x0c, // Temperature
+        ]);
+        mock.registers[0x78..0x7e].copy_from_slice(&[
+            0xb4, 0x00, // Battery voltage
+            0x32, 0x0a, // Charge current
+            0x00, 0x00, // Discharge current
+        ]);
+        mock.registers[Registers::BatteryLevel as usize] = 87;
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(pmic.measurements().unwrap(), Measurements {
+            battery_voltage: Millivolts(3168),
+            battery_charge_current: Milliamps(405),
+            battery_discharge_current: Milliamps(0),
+            acin_voltage: Millivolts(4787),
+            acin_current: Milliamps(640),
+            vbus_voltage: Millivolts(4787),
+            vbus_current: Milliamps(96),
+            temperature: Celsius(24),
+            battery_level: 87,
+        });
+
+        // Same numbers as reading one by one
+        assert_eq!(pmic.acin_current().unwrap(), 640);
+        assert_eq!(pmic.battery_voltage().unwrap(), 3168);
+    }
+
+    #[test]
+    fn summary() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x56..0x60].copy_from_slice(&[
+            0xb0, 0x00, // ACIN voltage
+            0x40, 0x00, // ACIN current
+            0x00, 0x00, // VBUS voltage
+            0x00, 0x00, // VBUS current
+            0x69, 0x0c, // Temperature
+        ]);
+        mock.registers[0x78..0x7e].copy_from_slice(&[
+            0xb4, 0x00, // Battery voltage
+            0x32, 0x0a, // Charge current
+            0x00, 0x00, // Discharge current
+        ]);
+        mock.registers[Registers::BatteryLevel as usize] = 87;
+        // ACIN plugged in and charging the battery
+        mock.registers[Registers::PowerStatus as usize] = 0b1100_0100;
+        mock.registers[Registers::ChargingStatus as usize] = 0b0110_0000;
+        let mut pmic = Axp209::new(mock);
+
+        assert_eq!(format!("{}", pmic.summary().unwrap()), "\
+            Battery:     3168mV, 405mA charging, 87%\n\
+            ACIN:        present, 4787mV, 640mA\n\
+            VBUS:        absent, 0mV, 0mA\n\
+            Temperature: 24°C\n");
+
+        pmic.device.registers[Registers::ChargingStatus as usize] = 0b1000_0000;
+        let summary = format!("{}", pmic.summary().unwrap());
+        assert!(summary.contains("Battery:     missing\n"));
+        assert!(summary.contains("(over temperature!)"));
+    }
+
+    /// Feeds `recv` back from a read of `register` and nothing else
+    fn adc(register: Registers, recv: &[u8]) -> Axp209<I2cMock> {
+        let transactions = [Transaction::write_read(ADDRESS, vec![register as u8], recv.to_vec())];
+
+        Axp209::new(I2cMock::new(&transactions))
+    }
+
+    #[test]
+    fn configure_writes_in_order() {
+        let config = Config::new()
+            .adc_control(AdcControl::all())
+            .dcdc2_voltage_mv(1400)
+            .charge_current_ma(500)
+            .charge_target_voltage(ChargeTargetVoltage::V4_20);
+
+        // Order of the builder calls doesn't matter, charging always goes
+        // first and the ADC last
+        let transactions = [
+            Transaction::write_read(ADDRESS, vec![0x33], vec![0xc8]),
+            Transaction::write(ADDRESS, vec![0x33, 0xc8]),
+            Transaction::write_read(ADDRESS, vec![0x33], vec![0xc8]),
+            Transaction::write(ADDRESS, vec![0x33, 0xc2]),
+            Transaction::write_read(ADDRESS, vec![0x23], vec![0x16]),
+            Transaction::write(ADDRESS, vec![0x23, 0x1c]),
+            Transaction::write(ADDRESS, vec![0x82, 0xff]),
+            Transaction::write(ADDRESS, vec![0x83, 0x8c]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        pmic.configure(&config).unwrap();
+        pmic.free().done();
+    }
+
+    #[test]
+    fn reset_defaults() {
+        let transactions = [
+            Transaction::write(ADDRESS, vec![0x33, 0xc8]),
+            Transaction::write(ADDRESS, vec![0x82, 0x83]),
+            Transaction::write(ADDRESS, vec![0x83, 0x80]),
+            Transaction::write(ADDRESS, vec![0x40, 0xd8]),
+            Transaction::write(ADDRESS, vec![0x41, 0xff]),
+            Transaction::write(ADDRESS, vec![0x42, 0x3b]),
+            Transaction::write(ADDRESS, vec![0x43, 0xc1]),
+            Transaction::write(ADDRESS, vec![0x44, 0x00]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        pmic.reset_defaults().unwrap();
+        pmic.free().done();
+    }
+
+    #[test]
+    fn battery_voltage_conversion() {
+        // 0xb40 steps of 1.1mV
+        let mut pmic = adc(Registers::BatteryVoltage, &[0xb4, 0x00]);
+        assert_eq!(pmic.battery_voltage().unwrap(), 3168);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn battery_voltage_rounding() {
+        // 0xb45 steps is 3173.5mV, which used to be cut down to 3173mV
+        let transactions = [
+            Transaction::write_read(0x34, vec![0x78], vec![0xb4, 0x05]),
+            Transaction::write_read(0x34, vec![0x78], vec![0xb4, 0x05]),
+            Transaction::write_read(0x34, vec![0x78], vec![0xb4, 0x04]),
+        ];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+        assert_eq!(pmic.battery_voltage_microvolts().unwrap(), 3_173_500);
+        assert_eq!(pmic.battery_voltage().unwrap(), 3174);
+        // 3172.4mV goes down
+        assert_eq!(pmic.battery_voltage().unwrap(), 3172);
+        pmic.free().done();
+    }
+
+    #[cfg(feature = "fixed")]
+    #[test]
+    fn fixed_point_readings() {
+        use fixed::types::I16F16;
+
+        let mut mock = MockI2c::new();
+        mock.registers[0x78..0x7a].copy_from_slice(&[0xb4, 0x05]);
+        mock.registers[0x58..0x5a].copy_from_slice(&[0x40, 0x03]);
+        mock.registers[0x5c..0x5e].copy_from_slice(&[0x10, 0x03]);
+        mock.registers[0x5e..0x60].copy_from_slice(&[0x5e, 0x05]);
+        let mut pmic = Axp209::new(mock);
+
+        // 3173.5mV, which the integer reader rounds up
+        let fixed = pmic.battery_voltage_fixed().unwrap();
+        assert_eq!(fixed, I16F16::from_num(3173.5));
+        assert!((fixed - I16F16::from_num(pmic.battery_voltage().unwrap().0)).abs() <= 0.5);
+
+        // 0x403 steps of 0.625mA is 641.875mA, which the integer reader
+        // rounds down
+        let fixed = pmic.acin_current_fixed().unwrap();
+        assert_eq!(fixed, I16F16::from_num(641.875));
+        let integer = I16F16::from_num(pmic.acin_current().unwrap().0);
+        assert!(fixed >= integer && fixed - integer < 1);
+
+        // 0x103 steps of 0.375mA is 97.125mA
+        let fixed = pmic.vbus_current_fixed().unwrap();
+        assert_eq!(fixed, I16F16::from_num(97.125));
+        let integer = I16F16::from_num(pmic.vbus_current().unwrap().0);
+        assert!(fixed >= integer && fixed - integer < 1);
+
+        // 0x5e5 steps of 0.1C from -144.7C
+        let fixed = pmic.temperature_fixed().unwrap();
+        assert!((fixed - I16F16::from_num(6.2)).abs() < 0.001);
+        assert_eq!(fixed.to_num::<i16>(), pmic.temperature().unwrap().0);
+    }
+
+    #[test]
+    fn battery_voltage_units() {
+        let mut pmic = adc(Registers::BatteryVoltage, &[0xb4, 0x00]);
+        let voltage: Millivolts = pmic.battery_voltage().unwrap();
+        pmic.free().done();
+
+        assert_eq!(voltage, 3168);
+        assert_eq!(voltage, Millivolts(3168));
+        assert_eq!(voltage.millivolts(), 3168);
+        assert_eq!(u16::from(voltage), 3168);
+        assert_eq!(format!("{}", voltage), "3168mV");
+
+        assert_eq!(format!("{}", Milliamps::new(405)), "405mA");
+        assert_eq!(format!("{}", Celsius::from(-12)), "-12°C");
+    }
+
+    #[test]
+    fn battery_charging_current_conversion() {
+        // 0x32a steps of 0.5mA
+        let mut pmic = adc(Registers::BatteryChargeCurrent, &[0x32, 0x0a]);
+        assert_eq!(pmic.battery_charging_current().unwrap(), 405);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn battery_discharging_current_conversion() {
+        // Five low bits instead of four, so 0x204 steps of 0.5mA
+        let mut pmic = adc(Registers::BatteryDischargeCurrent, &[0x10, 0x04]);
+        assert_eq!(pmic.battery_discharging_current().unwrap(), 258);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn raw_battery_currents() {
+        let mut pmic = adc(Registers::BatteryChargeCurrent, &[0x32, 0x0a]);
+        assert_eq!(pmic.raw_battery_charge_current().unwrap(), 0x32a);
+        pmic.free().done();
+
+        let mut pmic = adc(Registers::BatteryDischargeCurrent, &[0x10, 0x04]);
+        assert_eq!(pmic.raw_battery_discharge_current().unwrap(), 0x204);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn battery_currents_full_range() {
+        // The unused upper bits of the low register are ignored
+        let mut pmic = adc(Registers::BatteryChargeCurrent, &[0xff, 0xff]);
+        assert_eq!(pmic.raw_battery_charge_current().unwrap(), 0xfff);
+        pmic.free().done();
+
+        let mut pmic = adc(Registers::BatteryChargeCurrent, &[0xff, 0xff]);
+        assert_eq!(pmic.battery_charging_current().unwrap(), 2047);
+        pmic.free().done();
+
+        let mut pmic = adc(Registers::BatteryDischargeCurrent, &[0xff, 0xff]);
+        assert_eq!(pmic.raw_battery_discharge_current().unwrap(), 0x1fff);
+        pmic.free().done();
+
+        let mut pmic = adc(Registers::BatteryDischargeCurrent, &[0xff, 0xff]);
+        assert_eq!(pmic.battery_discharging_current().unwrap(), 4095);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn acin_voltage_conversion() {
+        // 0xb7d steps of 1.7mV, about 5V
+        let mut pmic = adc(Registers::AcinVoltage, &[0xb7, 0x0d]);
+        assert_eq!(pmic.acin_voltage().unwrap(), 4999);
+        pmic.free().done();
+
+        // Full scale
+        let mut pmic = adc(Registers::AcinVoltage, &[0xff, 0x0f]);
+        assert_eq!(pmic.acin_voltage().unwrap(), 6961);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn vbus_voltage_conversion() {
+        let mut pmic = adc(Registers::VbusVoltage, &[0xb0, 0x00]);
+        assert_eq!(pmic.vbus_voltage().unwrap(), 4787);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn vbus_current_conversion() {
+        // 0x100 steps of 0.375mA
+        let mut pmic = adc(Registers::VbusCurrent, &[0x10, 0x00]);
+        assert_eq!(pmic.vbus_current().unwrap(), 96);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn ts_voltage_conversion() {
+        // 0x3e8 steps of 0.8mV
+        let mut pmic = adc(Registers::BatteryTemperature, &[0x3e, 0x08]);
+        assert_eq!(pmic.ts_voltage().unwrap(), 800);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn adc_block() {
+        let transactions = [Transaction::write_read(
+            ADDRESS,
+            vec![0x56],
+            vec![
+                0xb0, 0x00, // ACIN voltage, 0xb00 steps of 1.7mV
+                0x40, 0x00, // ACIN current, 0x400 steps of 0.625mA
+                0x00, 0x00, // VBUS voltage
+                0x10, 0x00, // VBUS current, 0x100 steps of 0.375mA
+                0x5e, 0x05, // Temperature, 0x5e5 steps of 0.1C from -144.7C
+                0xff, 0xff, // Reserved
+                0x4e, 0x02, // TS, 0x4e2 steps of 0.8mV
+            ],
+        )];
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        assert_eq!(pmic.read_adc_block().unwrap(), AdcBlock {
+            acin_voltage: Millivolts(4787),
+            acin_current: Milliamps(640),
+            vbus_voltage: Millivolts(0),
+            vbus_current: Milliamps(96),
+            temperature: Celsius(6),
+            ts_voltage: Millivolts(1000),
+        });
+        pmic.free().done();
+    }
+
+    #[test]
+    fn monitor() {
+        let poll = |battery: u8, level: u8| [
+            Transaction::write_read(ADDRESS, vec![0x56], vec![0; 10]),
+            Transaction::write_read(ADDRESS, vec![0x78], vec![battery, 0, 0, 0, 0, 0]),
+            Transaction::write_read(ADDRESS, vec![0xb9], vec![level]),
+        ];
+        let transactions: std::vec::Vec<Transaction> = poll(0xb4, 87).iter()
+            .chain(poll(0xb5, 86).iter())
+            .cloned()
+            .collect();
+        let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+        let mut monitor = pmic.monitor();
+        let first = monitor.poll().unwrap();
+        assert_eq!(first.battery_voltage, 3168);
+        assert_eq!(first.battery_level, 87);
+
+        // The chip has moved on since
+        let second = monitor.next().unwrap().unwrap();
+        assert_eq!(second.battery_voltage, 3186);
+        assert_eq!(second.battery_level, 86);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn measurements_csv() {
+        let measurements = Measurements {
+            battery_voltage: Millivolts(3168),
+            battery_charge_current: Milliamps(405),
+            battery_discharge_current: Milliamps(0),
+            acin_voltage: Millivolts(4787),
+            acin_current: Milliamps(640),
+            vbus_voltage: Millivolts(0),
+            vbus_current: Milliamps(0),
+            temperature: Celsius(-12),
+            battery_level: 87,
+        };
+
+        let mut line = std::string::String::new();
+        measurements.write_csv(&mut line).unwrap();
+        assert_eq!(line, "3168,405,0,4787,640,0,0,-12,87");
+        assert_eq!(
+            Measurements::csv_header(),
+            "battery_voltage_mv,battery_charge_current_ma,battery_discharge_current_ma,\
+             acin_voltage_mv,acin_current_ma,vbus_voltage_mv,vbus_current_ma,temperature_c,battery_level"
+        );
+        assert_eq!(Measurements::csv_header().split(',').count(), line.split(',').count());
+    }
+
+    #[test]
+    fn calibration() {
+        let mut mock = MockI2c::new();
+        mock.registers[0x78..0x7a].copy_from_slice(&[0xb4, 0x00]);
+        let mut pmic = Axp209::new(mock);
+        assert_eq!(pmic.battery_voltage().unwrap(), 3168);
+        assert_eq!(pmic.calibration(), &Calibration::default());
+
+        let calibration = Calibration {
+            battery_voltage: ChannelCalibration::new(15, 100, 102),
+            ..Calibration::default()
+        };
+        let mut pmic = Axp209::with_calibration(pmic.free(), calibration);
+
+        // 3168 * 100 / 102 + 15
+        assert_eq!(pmic.battery_voltage().unwrap(), 3120);
+        assert_eq!(pmic.measurements().unwrap().battery_voltage, 3120);
+        // Other channels are left alone
+        assert_eq!(pmic.acin_voltage().unwrap(), 0);
+
+        let low = ChannelCalibration::new(-5000, 1, 0);
+        assert_eq!(low.apply(3168), 0);
+        assert_eq!(ChannelCalibration::IDENTITY.apply(3168), 3168);
+    }
+
+    #[test]
+    fn ts_resistance() {
+        // 1000mV at 80uA
+        let mut pmic = Axp209::new(MockI2c::new());
+        pmic.device.registers[0x62..0x64].copy_from_slice(&[0x4e, 0x02]);
+        assert_eq!(pmic.battery_ts_resistance_ohms(80).unwrap(), 12_500);
+        assert_eq!(pmic.battery_ts_resistance_ohms(20).unwrap(), 50_000);
+        assert_eq!(pmic.battery_ts_resistance_ohms(0), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn ipsout_voltage_conversion() {
+        // 0xa23 steps of 1.4mV
+        let mut pmic = adc(Registers::SystemIpsout, &[0xa2, 0x03]);
+        assert_eq!(pmic.ipsout_voltage().unwrap(), 3633);
+        pmic.free().done();
+
+        // Largest reading the ADC can give doesn't overflow
+        let mut pmic = adc(Registers::SystemIpsout, &[0xff, 0x0f]);
+        assert_eq!(pmic.ipsout_voltage().unwrap(), 5733);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn aps_voltage_conversion() {
+        let mut pmic = adc(Registers::SystemIpsout, &[0xa2, 0x03]);
+        assert_eq!(pmic.aps_voltage().unwrap(), 3633);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn temperature_conversion() {
+        // 0x69c steps of 0.1C from -144.7C
+        let mut pmic = adc(Registers::Temperature, &[0x69, 0x0c]);
+        assert_eq!(pmic.temperature().unwrap(), 24);
+        pmic.free().done();
+    }
+
+    #[test]
+    fn temperature_millicelsius_conversion() {
+        for &(recv, millicelsius, celsius) in &[
+            ([0x00, 0x00], -144_700, -144),
+            ([0x3e, 0x08], -44_700, -44),
+            ([0x5a, 0x07], 0, 0),
+            ([0x7d, 0x00], 55_300, 55),
+        ] {
+            let mut pmic = adc(Registers::Temperature, &recv);
+            assert_eq!(pmic.temperature_millicelsius().unwrap(), millicelsius);
+            pmic.free().done();
+
+            let mut pmic = adc(Registers::Temperature, &recv);
+            assert_eq!(pmic.temperature().unwrap(), celsius);
+            pmic.free().done();
+        }
+    }
+
+    #[test]
+    fn temperature_fahrenheit_conversion() {
+        for &(recv, fahrenheit) in &[
+            ([0x5a, 0x07], 32),    // Freezing
+            ([0x7d, 0x00], 132),   // 55.3C is 131.54F
+            ([0x41, 0x07], -40),   // Where the scales meet
+            ([0x00, 0x00], -228),  // -144.7C is -228.46F
+        ] {
+            let mut pmic = adc(Registers::Temperature, &recv);
+            assert_eq!(pmic.temperature_fahrenheit().unwrap(), fahrenheit);
+            pmic.free().done();
+        }
+    }
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn defmt_format() {
+        fn format<T: defmt::Format>() {}
+
+        format::<AdcControl>();
+        format::<PowerStatus>();
+        format::<PowerControl>();
+        format::<ChargingStatus>();
+        format::<IrqStatus>();
+        format::<IrqEnable>();
+        format::<Measurements>();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let measurements = Measurements {
+            battery_voltage: Millivolts(3168),
+            battery_charge_current: Milliamps(405),
+            battery_discharge_current: Milliamps(0),
+            acin_voltage: Millivolts(4787),
+            acin_current: Milliamps(640),
+            vbus_voltage: Millivolts(4787),
+            vbus_current: Milliamps(96),
+            temperature: Celsius(-3),
+            battery_level: 87,
+        };
+        let json = serde_json::to_string(&measurements).unwrap();
+        assert_eq!(serde_json::from_str::<Measurements>(&json).unwrap(), measurements);
+
+        let status = PowerStatus::ACIN_PRESENT | PowerStatus::VBUS_PRESENT;
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(serde_json::from_str::<PowerStatus>(&json).unwrap(), status);
+
+        let status = ChargingStatus::CHARGING;
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(serde_json::from_str::<ChargingStatus>(&json).unwrap(), status);
+
+        let adc = AdcControl::all();
+        let json = serde_json::to_string(&adc).unwrap();
+        assert_eq!(serde_json::from_str::<AdcControl>(&json).unwrap(), adc);
+    }
+
+    #[cfg(feature = "async")]
+    mod asynch {
+        use core::future::Future;
+        use core::pin::pin;
+        use core::task::{Context, Poll, Waker};
+
+        use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction};
+
+        use crate::asynch::Axp209;
+        use crate::{Rail, ADDRESS};
+
+        /// The mock bus never has to wait, so there's no need for a real executor
+        fn block_on<F: Future>(future: F) -> F::Output {
+            let mut future = pin!(future);
+            let mut context = Context::from_waker(Waker::noop());
+
+            loop {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                    return output;
+                }
+            }
+        }
+
+        #[test]
+        fn battery_readings() {
+            let transactions = [
+                Transaction::write_read(ADDRESS, vec![0x78], vec![0xb4, 0x00]),
+                Transaction::write_read(ADDRESS, vec![0x7c], vec![0x10, 0x04]),
+                Transaction::write_read(ADDRESS, vec![0xb9], vec![0x80 | 55]),
+            ];
+            let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+            block_on(async {
+                assert_eq!(pmic.battery_voltage().await.unwrap(), 3168);
+                assert_eq!(pmic.battery_discharging_current().await.unwrap(), 258);
+                assert_eq!(pmic.battery_level().await.unwrap(), 55);
+            });
+            pmic.free().done();
+        }
+
+        #[test]
+        fn rail_settings() {
+            let transactions = [
+                Transaction::write_read(ADDRESS, vec![0x12], vec![0b1011_0001]),
+                Transaction::write(ADDRESS, vec![0x12, 0b1111_0001]),
+                Transaction::write_read(ADDRESS, vec![0x23], vec![0xc0]),
+                Transaction::write(ADDRESS, vec![0x23, 0xd6]),
+            ];
+            let mut pmic = Axp209::new(I2cMock::new(&transactions));
+
+            block_on(async {
+                pmic.set_rail_enabled(Rail::Ldo3, true).await.unwrap();
+                pmic.set_dcdc2_voltage_mv(1250).await.unwrap();
+            });
+            pmic.free().done();
+        }
+    }
+
+    /// These need a real C.H.I.P. and the kernel's AXP20X drivers disabled,
+    /// run them with `cargo test --features hardware-tests`
+    #[cfg(feature = "hardware-tests")]
+    mod hardware {
+        extern crate linux_embedded_hal as linux_hal;
+
+        use super::*;
+
+        use self::linux_hal::{SysfsPin, I2cdev};
+        use hal::digital::{InputPin, OutputPin};
+
+        #[test]
+        fn permissions() {
+            let mut gpio = SysfsPin::new(135);
+            let state = gpio.is_low().unwrap();
+
+            if state {
+                gpio.set_high().unwrap();
+            } else {
+                gpio.set_low().unwrap();
+            }
+        }
+
+        #[test]
+        fn battery_level() {
+            let i2c = I2cdev::new("/dev/i2c-0").unwrap();
+
+            let mut pmic = Axp209::new(i2c);
+            let _level = pmic.battery_level().unwrap();
+
+            // Values for 'level' can be either the percentage, or
+            // 0x7F if the battery is missing
+        }
     }
 }
 
@@ -0,0 +1,64 @@
+//! A snapshot of everything the ADC measures, as returned by
+//! `Axp209::measurements()`, and the smaller `AdcBlock` from
+//! `Axp209::read_adc_block()`.
+
+use core::fmt;
+
+use crate::units::{Celsius, Milliamps, Millivolts};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measurements {
+    pub battery_voltage: Millivolts,
+    pub battery_charge_current: Milliamps,
+    pub battery_discharge_current: Milliamps,
+    pub acin_voltage: Millivolts,
+    pub acin_current: Milliamps,
+    pub vbus_voltage: Millivolts,
+    pub vbus_current: Milliamps,
+    pub temperature: Celsius,
+    /// In percentage, or `BATTERY_LEVEL_MISSING`
+    pub battery_level: u8,
+}
+
+impl Measurements {
+    /// Column names matching `write_csv()`
+    pub const fn csv_header() -> &'static str {
+        "battery_voltage_mv,battery_charge_current_ma,battery_discharge_current_ma,\
+         acin_voltage_mv,acin_current_ma,vbus_voltage_mv,vbus_current_ma,temperature_c,battery_level"
+    }
+
+    /// Writes the numbers as one comma separated line, without units or a
+    /// line ending, in the same order as `csv_header()`
+    pub fn write_csv(&self, buf: &mut impl fmt::Write) -> fmt::Result {
+        write!(
+            buf,
+            "{},{},{},{},{},{},{},{},{}",
+            self.battery_voltage.0,
+            self.battery_charge_current.0,
+            self.battery_discharge_current.0,
+            self.acin_voltage.0,
+            self.acin_current.0,
+            self.vbus_voltage.0,
+            self.vbus_current.0,
+            self.temperature.0,
+            self.battery_level,
+        )
+    }
+}
+
+/// The ADC channels stored back to back from 0x56 to 0x63, which can all be
+/// read at once
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdcBlock {
+    pub acin_voltage: Millivolts,
+    pub acin_current: Milliamps,
+    pub vbus_voltage: Millivolts,
+    pub vbus_current: Milliamps,
+    pub temperature: Celsius,
+    /// The TS pin, usually a battery thermistor
+    pub ts_voltage: Millivolts,
+}
@@ -0,0 +1,45 @@
+//! A read loop for simple polling code, from `Axp209::monitor()`. Every
+//! call to `poll()` (or `next()`, it's an endless iterator too) reads a
+//! fresh set of `Measurements`:
+//!
+//! ```ignore
+//!     for measurements in pmic.monitor().take(10) {
+//!         println!("{}", measurements?.battery_voltage);
+//!         delay.delay_ms(1000);
+//!     }
+//! ```
+
+use hal::i2c::I2c;
+
+use crate::measurements::Measurements;
+use crate::{Axp209, Error};
+
+pub struct Monitor<'a, I2C> {
+    pmic: &'a mut Axp209<I2C>,
+}
+
+impl<'a, I2C, E> Monitor<'a, I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    pub(crate) fn new(pmic: &'a mut Axp209<I2C>) -> Self {
+        Monitor { pmic }
+    }
+
+    /// Same as `Axp209::measurements()`
+    pub fn poll(&mut self) -> Result<Measurements, Error<E>> {
+        self.pmic.measurements()
+    }
+}
+
+impl<'a, I2C, E> Iterator for Monitor<'a, I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    type Item = Result<Measurements, Error<E>>;
+
+    /// Never runs out, bus errors included
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.poll())
+    }
+}
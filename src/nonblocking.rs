@@ -0,0 +1,78 @@
+//! `nb` flavoured versions of the ADC readers, behind the `nb` feature, for
+//! schedulers that would rather poll than wait:
+//!
+//! ```ignore
+//!     let voltage = nb::block!(pmic.try_battery_voltage())?;
+//! ```
+//!
+//! The blocking I2C traits never actually say "try again later", so for
+//! now the only thing turned into `WouldBlock` is losing arbitration to
+//! another master on the bus. Everything else is passed on as
+//! `nb::Error::Other`.
+
+use hal::i2c::{ErrorKind, I2c};
+
+use crate::units::{Celsius, Milliamps, Millivolts};
+use crate::{Axp209, Error};
+
+/// Maps a busy bus to `WouldBlock` and leaves every other error alone
+pub(crate) fn would_block<T, E>(result: Result<T, Error<E>>) -> nb::Result<T, Error<E>>
+where
+    E: hal::i2c::Error,
+{
+    result.map_err(|error| match error {
+        Error::I2c(ref e) if e.kind() == ErrorKind::ArbitrationLoss => nb::Error::WouldBlock,
+        other => nb::Error::Other(other),
+    })
+}
+
+impl<I2C, E> Axp209<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: hal::i2c::Error,
+{
+    /// See `battery_voltage()`
+    pub fn try_battery_voltage(&mut self) -> nb::Result<Millivolts, Error<E>> {
+        would_block(self.battery_voltage())
+    }
+
+    /// See `battery_charging_current()`
+    pub fn try_battery_charging_current(&mut self) -> nb::Result<Milliamps, Error<E>> {
+        would_block(self.battery_charging_current())
+    }
+
+    /// See `battery_discharging_current()`
+    pub fn try_battery_discharging_current(&mut self) -> nb::Result<Milliamps, Error<E>> {
+        would_block(self.battery_discharging_current())
+    }
+
+    /// See `acin_voltage()`
+    pub fn try_acin_voltage(&mut self) -> nb::Result<Millivolts, Error<E>> {
+        would_block(self.acin_voltage())
+    }
+
+    /// See `acin_current()`
+    pub fn try_acin_current(&mut self) -> nb::Result<Milliamps, Error<E>> {
+        would_block(self.acin_current())
+    }
+
+    /// See `vbus_voltage()`
+    pub fn try_vbus_voltage(&mut self) -> nb::Result<Millivolts, Error<E>> {
+        would_block(self.vbus_voltage())
+    }
+
+    /// See `vbus_current()`
+    pub fn try_vbus_current(&mut self) -> nb::Result<Milliamps, Error<E>> {
+        would_block(self.vbus_current())
+    }
+
+    /// See `temperature()`
+    pub fn try_temperature(&mut self) -> nb::Result<Celsius, Error<E>> {
+        would_block(self.temperature())
+    }
+
+    /// See `battery_level()`
+    pub fn try_battery_level(&mut self) -> nb::Result<u8, Error<E>> {
+        would_block(self.battery_level())
+    }
+}
@@ -0,0 +1,99 @@
+//! The PEK (power enable key) register (0x36) sets how long the power
+//! button has to be held for the different actions. On a handheld this is
+//! what makes the button feel right.
+
+/// How long the key has to be held to power the system on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PekPowerOnTime {
+    Ms128 = 0b00,
+    S3 = 0b01,
+    S1 = 0b10,
+    S2 = 0b11,
+}
+
+/// How long the key has to be held for it to count as a long press
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PekLongPressTime {
+    S1 = 0b00,
+    S1_5 = 0b01,
+    S2 = 0b10,
+    S2_5 = 0b11,
+}
+
+/// How long the key has to be held to force the system off
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PekPowerOffTime {
+    S4 = 0b00,
+    S6 = 0b01,
+    S8 = 0b10,
+    S10 = 0b11,
+}
+
+/// How long after the power-on sequence finishes the PWROK pin goes high,
+/// releasing the SoC from reset. Bit 2 of the register, kept out of
+/// `PekConfig`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PwrokDelay {
+    Ms8 = 0,
+    Ms64 = 1,
+}
+
+impl PwrokDelay {
+    /// Decodes bit 2 of register 0x36
+    pub fn from_register(value: u8) -> Self {
+        if value & 0b100 != 0 {
+            PwrokDelay::Ms64
+        } else {
+            PwrokDelay::Ms8
+        }
+    }
+
+    /// The delay as bit 2 of register 0x36, the rest left clear
+    pub fn to_register(&self) -> u8 {
+        (*self as u8) << 2
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PekConfig {
+    pub power_on_time: PekPowerOnTime,
+    pub long_press_time: PekLongPressTime,
+    /// Whether holding the key for `power_off_time` shuts the system down
+    pub power_off_enabled: bool,
+    pub power_off_time: PekPowerOffTime,
+}
+
+impl PekConfig {
+    /// Bit 2 (the PWROK delay) isn't part of the config and is ignored
+    pub fn from_register(value: u8) -> Self {
+        Self {
+            power_on_time: match (value >> 6) & 0b11 {
+                0b00 => PekPowerOnTime::Ms128,
+                0b01 => PekPowerOnTime::S3,
+                0b10 => PekPowerOnTime::S1,
+                _ => PekPowerOnTime::S2,
+            },
+            long_press_time: match (value >> 4) & 0b11 {
+                0b00 => PekLongPressTime::S1,
+                0b01 => PekLongPressTime::S1_5,
+                0b10 => PekLongPressTime::S2,
+                _ => PekLongPressTime::S2_5,
+            },
+            power_off_enabled: value & (1 << 3) != 0,
+            power_off_time: match value & 0b11 {
+                0b00 => PekPowerOffTime::S4,
+                0b01 => PekPowerOffTime::S6,
+                0b10 => PekPowerOffTime::S8,
+                _ => PekPowerOffTime::S10,
+            },
+        }
+    }
+
+    /// Register 0x36 with the PWROK delay bit left clear
+    pub fn to_register(&self) -> u8 {
+        ((self.power_on_time as u8) << 6)
+            | ((self.long_press_time as u8) << 4)
+            | ((self.power_off_enabled as u8) << 3)
+            | self.power_off_time as u8
+    }
+}
@@ -1,10 +1,16 @@
 //! The Power Control register allows turning on and off various voltages
 //! supplied by the chip. This allows things like shutting off the WiFi
 //! chip or USB/5v rails on the NTC C.H.I.P. computer at their source. 
+//!
+//! On the AXP209 this register (0x12) is the only place EXTEN and DCDC2 are
+//! switched. Some of its relatives (the AXP152 and AXP22x) have an extra
+//! output control register at 0x10, but there's nothing at 0x10 here, so
+//! use `Axp209::set_rail_enabled()` with `Rail::Exten` or `Rail::Dcdc2`.
 
 
 bitflags! {
     /// Holds the state of the register. Changes will need to be committed manually
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct PowerControl: u8 {
         /// The voltage supplied on the LDO3 pin
         const LDO3 = 1 << 6;
@@ -33,4 +39,34 @@ impl PowerControl {
             bits: value
         }
     }
-}
\ No newline at end of file
+}
+
+/// The Power Control register is also known as the output control
+/// register, and some prefer to think of it that way when setting all the
+/// rails at once. See `Axp209::set_output_control()`.
+pub type OutputControl = PowerControl;
+
+/// The individual outputs gated by the Power Control register
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rail {
+    Dcdc2,
+    Dcdc3,
+    Ldo2,
+    Ldo3,
+    Ldo4,
+    Exten,
+}
+
+impl Rail {
+    /// The bit in the Power Control register that switches this rail
+    pub fn flag(&self) -> PowerControl {
+        match *self {
+            Rail::Dcdc2 => PowerControl::DCDC2,
+            Rail::Dcdc3 => PowerControl::DCDC3,
+            Rail::Ldo2 => PowerControl::LDO2,
+            Rail::Ldo3 => PowerControl::LDO3,
+            Rail::Ldo4 => PowerControl::LDO4,
+            Rail::Exten => PowerControl::EXTEN,
+        }
+    }
+}
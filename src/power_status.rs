@@ -1,7 +1,11 @@
+use crate::charging_status::ChargingStatus;
+
 bitflags! {
     /// The Power Status register hold information about the power status.
     /// All bits here are read-only.
 
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct PowerStatus: u8 {
         /// Whether there is power coming in on the ACIN pin or not. Note that
         /// this is not the power coming from USB on the NTC C.H.I.P. but it
@@ -16,8 +20,8 @@ bitflags! {
         const VBUS_USABLE = 1 << 4;
         /// Whether the incoming voltage is above the configured VHOLD value.
         const VBUS_ABOVE_HOLD = 1 << 3;
-        /// State of the battery (Charging = true, Discharging = false)
-        const DISCHARGING = 1 << 2;
+        /// Direction of the battery current (Charging = true, Discharging = false)
+        const CHARGING = 1 << 2;
         /// I'm not quite sure here. The datasheet says a short circuit between VBUS and ACIN
         const SHORT_CIRCUIT = 1 << 1; // A fine movie
         /// If the chip powers on the system when power is appled. Not sure where this can be
@@ -34,4 +38,58 @@ impl PowerStatus {
             bits: value
         }
     }
-}
\ No newline at end of file
+
+    pub fn acin_present(&self) -> bool {
+        self.contains(Self::ACIN_PRESENT)
+    }
+
+    pub fn acin_valid(&self) -> bool {
+        self.contains(Self::ACIN_USABLE)
+    }
+
+    pub fn vbus_present(&self) -> bool {
+        self.contains(Self::VBUS_PRESENT)
+    }
+
+    pub fn vbus_valid(&self) -> bool {
+        self.contains(Self::VBUS_USABLE)
+    }
+
+    /// Whether current is flowing into the battery rather than out of it
+    pub fn charging(&self) -> bool {
+        self.contains(Self::CHARGING)
+    }
+
+    /// The chip prefers ACIN over VBUS when both are usable, so the system is
+    /// only running off of VBUS when it's the sole usable input.
+    pub fn powered_by_vbus(&self) -> bool {
+        self.vbus_valid() && !self.acin_valid()
+    }
+}
+
+/// A single answer to what the chip is up to, from `Axp209::power_mode()`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerMode {
+    /// Neither ACIN nor VBUS is usable, so the battery is running the system
+    Active,
+    /// External power is in and the battery is charging from it
+    ChargingOnly,
+    /// External power is in and the charger has nothing to do, because the
+    /// battery is full or missing
+    Standby,
+}
+
+impl PowerMode {
+    /// Usable external power decides between `Active` and the other two,
+    /// then the charging bit of the charging status decides between
+    /// `ChargingOnly` and `Standby`.
+    pub fn from_status(power: PowerStatus, charging: ChargingStatus) -> Self {
+        if !power.acin_valid() && !power.vbus_valid() {
+            PowerMode::Active
+        } else if charging.charging() {
+            PowerMode::ChargingOnly
+        } else {
+            PowerMode::Standby
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! The shutdown register (0x32) holds a mix of settings: the shutdown bit
+//! itself, battery detection, the CHGLED pin and the shutdown timing.
+
+/// What the CHGLED pin does when it's under manual control. The pin can
+/// only sink current, so an LED wired to it is lit while it's low.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChgLedMode {
+    /// Not driven at all, so the LED is off
+    HighImpedance = 0b00,
+    /// Blinks at 1Hz with a 25% duty cycle
+    Flash1Hz = 0b01,
+    /// Blinks at 4Hz with a 50% duty cycle
+    Flash4Hz = 0b10,
+    /// Held low, so the LED is on
+    OutputLow = 0b11,
+}
+
+impl ChgLedMode {
+    /// Decodes bits 5-4 of register 0x32
+    pub fn from_register(value: u8) -> Self {
+        match (value >> 4) & 0b11 {
+            0b00 => ChgLedMode::HighImpedance,
+            0b01 => ChgLedMode::Flash1Hz,
+            0b10 => ChgLedMode::Flash4Hz,
+            _ => ChgLedMode::OutputLow,
+        }
+    }
+
+    /// The mode as bits 5-4 of register 0x32, the rest left clear
+    pub fn to_register(&self) -> u8 {
+        (*self as u8) << 4
+    }
+}
+
+/// How long N_OE is held after a shutdown is requested before the outputs
+/// are actually turned off, giving the rest of the board time to wrap up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoeDelay {
+    Ms128 = 0b00,
+    S1 = 0b01,
+    S2 = 0b10,
+    S3 = 0b11,
+}
+
+impl NoeDelay {
+    /// Decodes bits 1-0 of register 0x32
+    pub fn from_register(value: u8) -> Self {
+        match value & 0b11 {
+            0b00 => NoeDelay::Ms128,
+            0b01 => NoeDelay::S1,
+            0b10 => NoeDelay::S2,
+            _ => NoeDelay::S3,
+        }
+    }
+
+    /// The delay as bits 1-0 of register 0x32, the rest left clear
+    pub fn to_register(&self) -> u8 {
+        *self as u8
+    }
+}
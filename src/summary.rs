@@ -0,0 +1,51 @@
+//! Everything worth knowing about the chip at a glance, as returned by
+//! `Axp209::summary()`. The `Display` output looks something like:
+//!
+//! ```text
+//! Battery:     3168mV, 405mA charging, 87%
+//! ACIN:        present, 4787mV, 640mA
+//! VBUS:        absent, 0mV, 0mA
+//! Temperature: 24°C
+//! ```
+
+use core::fmt;
+
+use crate::charging_status::ChargingStatus;
+use crate::measurements::Measurements;
+use crate::power_status::PowerStatus;
+use crate::BATTERY_LEVEL_MISSING;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PmicSummary {
+    pub measurements: Measurements,
+    pub power_status: PowerStatus,
+    pub charging_status: ChargingStatus,
+}
+
+fn presence(present: bool) -> &'static str {
+    if present { "present" } else { "absent" }
+}
+
+impl fmt::Display for PmicSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let m = &self.measurements;
+
+        if !self.charging_status.battery_present() || m.battery_level == BATTERY_LEVEL_MISSING {
+            writeln!(f, "Battery:     missing")?;
+        } else if self.power_status.charging() {
+            writeln!(f, "Battery:     {}, {} charging, {}%", m.battery_voltage, m.battery_charge_current, m.battery_level)?;
+        } else {
+            writeln!(f, "Battery:     {}, {} discharging, {}%", m.battery_voltage, m.battery_discharge_current, m.battery_level)?;
+        }
+
+        writeln!(f, "ACIN:        {}, {}, {}", presence(self.power_status.acin_present()), m.acin_voltage, m.acin_current)?;
+        writeln!(f, "VBUS:        {}, {}, {}", presence(self.power_status.vbus_present()), m.vbus_voltage, m.vbus_current)?;
+
+        write!(f, "Temperature: {}", m.temperature)?;
+        if self.charging_status.over_temperature() {
+            write!(f, " (over temperature!)")?;
+        }
+
+        writeln!(f)
+    }
+}
@@ -11,7 +11,7 @@
 //! required. Here's an example of how to set the timer for five minute and
 //! busywait for it to expire:
 //! 
-//! ```
+//! ```ignore
 //!     // Use the timer on the NTC C.H.I.P. on Linux
 //!     let i2c = I2cdev::new("/dev/i2c-0").unwrap();
 //!     let mut pmic = Axp209::new(i2c);
@@ -60,7 +60,7 @@ impl TimerControl {
 
     /// Read the number of minutes the timer is counting down from. 
     pub fn minutes(&self) -> u8 {
-        let mut value: TimerControl = self.clone();
+        let mut value: TimerControl = *self;
         value.set(Self::TIMER_EXPIRED, false);
 
         value.bits
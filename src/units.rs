@@ -0,0 +1,125 @@
+//! Thin wrappers around the numbers coming out of the ADC so a voltage
+//! can't be mistaken for a current. They still compare equal to the plain
+//! number, and `.0` or `From` get it back out.
+
+use core::fmt;
+
+/// A voltage, in millivolts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Millivolts(pub u16);
+
+impl Millivolts {
+    pub const fn new(mv: u16) -> Self {
+        Millivolts(mv)
+    }
+
+    pub const fn millivolts(&self) -> u16 {
+        self.0
+    }
+}
+
+impl fmt::Display for Millivolts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}mV", self.0)
+    }
+}
+
+impl From<u16> for Millivolts {
+    fn from(mv: u16) -> Self {
+        Millivolts(mv)
+    }
+}
+
+impl From<Millivolts> for u16 {
+    fn from(value: Millivolts) -> u16 {
+        value.0
+    }
+}
+
+impl PartialEq<u16> for Millivolts {
+    fn eq(&self, other: &u16) -> bool {
+        self.0 == *other
+    }
+}
+
+/// A current, in milliamps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Milliamps(pub u16);
+
+impl Milliamps {
+    pub const fn new(ma: u16) -> Self {
+        Milliamps(ma)
+    }
+
+    pub const fn milliamps(&self) -> u16 {
+        self.0
+    }
+}
+
+impl fmt::Display for Milliamps {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}mA", self.0)
+    }
+}
+
+impl From<u16> for Milliamps {
+    fn from(ma: u16) -> Self {
+        Milliamps(ma)
+    }
+}
+
+impl From<Milliamps> for u16 {
+    fn from(value: Milliamps) -> u16 {
+        value.0
+    }
+}
+
+impl PartialEq<u16> for Milliamps {
+    fn eq(&self, other: &u16) -> bool {
+        self.0 == *other
+    }
+}
+
+/// A temperature, in whole degrees celcius
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Celsius(pub i16);
+
+impl Celsius {
+    pub const fn new(degrees: i16) -> Self {
+        Celsius(degrees)
+    }
+
+    pub const fn degrees(&self) -> i16 {
+        self.0
+    }
+}
+
+impl fmt::Display for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}°C", self.0)
+    }
+}
+
+impl From<i16> for Celsius {
+    fn from(degrees: i16) -> Self {
+        Celsius(degrees)
+    }
+}
+
+impl From<Celsius> for i16 {
+    fn from(value: Celsius) -> i16 {
+        value.0
+    }
+}
+
+impl PartialEq<i16> for Celsius {
+    fn eq(&self, other: &i16) -> bool {
+        self.0 == *other
+    }
+}
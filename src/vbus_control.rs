@@ -0,0 +1,29 @@
+//! The VBUS-IPSOUT power path register (0x30) decides how much the system
+//! is allowed to draw from VBUS (usually the USB port).
+
+/// How much current can be drawn from VBUS. USB hosts hand out 500mA unless
+/// something better has been negotiated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VbusCurrentLimit {
+    Ma900 = 0b00,
+    Ma500 = 0b01,
+    Ma100 = 0b10,
+    Unlimited = 0b11,
+}
+
+impl VbusCurrentLimit {
+    /// Decodes bits 1-0 of register 0x30
+    pub fn from_register(value: u8) -> Self {
+        match value & 0b11 {
+            0b00 => VbusCurrentLimit::Ma900,
+            0b01 => VbusCurrentLimit::Ma500,
+            0b10 => VbusCurrentLimit::Ma100,
+            _ => VbusCurrentLimit::Unlimited,
+        }
+    }
+
+    /// The limit as bits 1-0 of register 0x30, the rest left clear
+    pub fn to_register(&self) -> u8 {
+        *self as u8
+    }
+}
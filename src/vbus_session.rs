@@ -0,0 +1,34 @@
+bitflags! {
+    /// The USB OTG VBUS status register (0x02) reports VBUS the way an OTG
+    /// controller sees it, which is what matters when the board might be
+    /// the one supplying VBUS.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct VbusSessionStatus: u8 {
+        /// VBUS is high enough for an A-device (host) session
+        const VBUS_VALID = 1 << 2;
+        /// VBUS is high enough for an A or B-device session to be valid
+        const SESSION_VALID = 1 << 1;
+        /// VBUS has dropped below the session end threshold
+        const SESSION_END = 1 << 0;
+    }
+}
+
+impl VbusSessionStatus {
+    pub fn new(value: u8) -> Self {
+        Self {
+            bits: value
+        }
+    }
+
+    pub fn vbus_valid(&self) -> bool {
+        self.contains(Self::VBUS_VALID)
+    }
+
+    pub fn session_valid(&self) -> bool {
+        self.contains(Self::SESSION_VALID)
+    }
+
+    pub fn session_ended(&self) -> bool {
+        self.contains(Self::SESSION_END)
+    }
+}
@@ -0,0 +1,39 @@
+//! Register 0x25 controls how fast DCDC2 moves to a new voltage (VRC,
+//! voltage ramp control), which matters when scaling the CPU rail up and
+//! down. The same register holds LDO3's ramp bits, which are left alone.
+
+/// Time taken for each 25mV step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VrcSlope {
+    /// 25mV every 15.625us, or 1.6mV/us
+    Us15_625 = 0,
+    /// 25mV every 31.25us, or 0.8mV/us
+    Us31_25 = 1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dcdc2Vrc {
+    /// When disabled DCDC2 jumps straight to the new voltage
+    pub enabled: bool,
+    pub slope: VrcSlope,
+}
+
+impl Dcdc2Vrc {
+    /// The bits of the register that belong to DCDC2
+    pub const MASK: u8 = 0b101;
+
+    /// Decodes bits 2 (enable) and 0 (slope) of register 0x25
+    pub fn from_register(value: u8) -> Self {
+        Dcdc2Vrc {
+            enabled: value & 0b100 != 0,
+            slope: if value & 0b001 != 0 { VrcSlope::Us31_25 } else { VrcSlope::Us15_625 },
+        }
+    }
+
+    /// The settings as bits 2 and 0 of register 0x25, LDO3's bits left clear
+    pub fn to_register(&self) -> u8 {
+        let enabled = if self.enabled { 0b100 } else { 0 };
+
+        enabled | self.slope as u8
+    }
+}